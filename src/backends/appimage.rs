@@ -0,0 +1,581 @@
+//! AppImage backend for installing self-contained applications from a
+//! catalog feed.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::config::AppImageConfig;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, run};
+use super::{io_error, Backend};
+
+/// Environment variable naming the catalog, a path or an http(s) URL.
+const CATALOG_ENV: &str = "FI_APPIMAGE_CATALOG";
+
+/// Backend implementation for AppImages.
+///
+/// Applications come from a JSON catalog, either a local file or a feed on
+/// a web server or local mirror, given by `FI_APPIMAGE_CATALOG`, by `catalog`
+/// in the `[appimage]` configuration table, or placed at
+/// `$XDG_DATA_HOME/fi/appimage-catalog.json`. Installed AppImages go to
+/// `~/Applications`, get a `.desktop` entry, and are tracked in
+/// `$XDG_DATA_HOME/fi/appimages.json`.
+pub struct AppImageBackend {
+    /// The catalog location, `None` if there is no catalog.
+    catalog: Option<String>,
+}
+
+impl AppImageBackend {
+    /// Creates the backend, reading the catalog from the configuration
+    /// unless `FI_APPIMAGE_CATALOG` is set.
+    pub fn new(config: &AppImageConfig) -> Self {
+        Self { catalog: catalog_source(config.catalog.as_deref()) }
+    }
+
+    /// Loads the catalog.
+    async fn load_catalog(&self) -> Result<Catalog, BackendError> {
+        let source = self.catalog.as_deref().ok_or_else(|| {
+            BackendError::Io(format!("no AppImage catalog configured, set {}", CATALOG_ENV))
+        })?;
+        fetch_catalog(source).await
+    }
+}
+
+#[async_trait]
+impl Backend for AppImageBackend {
+    fn name(&self) -> &str { "appimage" }
+
+    fn exists(&self) -> bool {
+        self.catalog.is_some()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching AppImage catalog...");
+
+        let catalog = self.load_catalog().await?;
+        let installed = load_installed().await?;
+        let query = query.to_lowercase();
+
+        let results = catalog
+            .apps
+            .iter()
+            .filter(|app| {
+                app.id.to_lowercase().contains(&query)
+                    || app.name.to_lowercase().contains(&query)
+                    || app.description.to_lowercase().contains(&query)
+            })
+            .map(|app| {
+                let installed_version = installed.apps.get(&app.id).map(|i| i.version.clone());
+                SearchResult {
+                    backend: "appimage".into(),
+                    name: app.name.clone(),
+                    identifier: app.id.clone(),
+                    description: app.description.clone(),
+                    version: Some(app.version.clone()),
+                    installed: installed_version.is_some(),
+                    installed_version,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        pb.finish_with_message("AppImage search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing AppImages...");
+
+        let results = load_installed()
+            .await?
+            .apps
+            .into_iter()
+            .map(|(id, app)| app.to_result(id))
+            .collect();
+
+        pb.finish_with_message("AppImage listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking AppImage catalog for updates...");
+
+        let (catalog, installed) = tokio::join!(self.load_catalog(), load_installed());
+        let catalog = catalog?;
+
+        // The catalog is the source of truth, so any other version counts as
+        // an update, even if a mirror went back a version
+        let results = installed?
+            .apps
+            .into_iter()
+            .filter_map(|(id, app)| {
+                let latest = catalog.apps.iter().find(|a| a.id == id)?;
+                if latest.version == app.version {
+                    return None;
+                }
+                let mut result = app.to_result(id);
+                result.version = Some(latest.version.clone());
+                Some(result)
+            })
+            .collect();
+
+        pb.finish_with_message("AppImage update check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let catalog = self.load_catalog().await?;
+        let app = catalog.find(&pkg.identifier)?;
+
+        Ok(PackageInfo {
+            backend: "appimage".into(),
+            name: app.name.clone(),
+            version: Some(app.version.clone()),
+            description: app.description.clone(),
+            license: app.license.clone(),
+            homepage: app.homepage.clone(),
+            repository: Some(catalog.source.clone()),
+            ..PackageInfo::default()
+        })
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], _assume_yes: bool) -> Result<(), BackendError> {
+        let catalog = self.load_catalog().await?;
+        let mut installed = load_installed().await?;
+
+        for pkg in pkgs {
+            let app = catalog.find(&pkg.identifier)?;
+            let entry = install_app(&catalog.source, app).await?;
+
+            // Saved after every app so a failure later on doesn't lose track
+            // of the ones already installed
+            installed.apps.insert(app.id.clone(), entry);
+            save_installed(&installed).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        let mut installed = load_installed().await?;
+
+        for pkg in pkgs {
+            let Some(app) = installed.apps.remove(&pkg.identifier) else {
+                continue;
+            };
+
+            for path in [&app.path, &app.desktop_entry] {
+                match tokio::fs::remove_file(path).await {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(io_error(path, e)),
+                    _ => {}
+                }
+            }
+            save_installed(&installed).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        // Installing again replaces the AppImage and its desktop entry
+        self.install(pkgs, false).await
+    }
+}
+
+/// The catalog feed.
+///
+/// Example:
+/// ```text
+/// {"apps": [{"id": "krita", "name": "Krita", "version": "5.2.2",
+///   "description": "Digital painting", "url": "krita-5.2.2-x86_64.AppImage",
+///   "sha256": "8c3f...", "categories": ["Graphics"]}]}
+/// ```
+///
+/// A relative `url` is resolved against the catalog's own location, so a
+/// mirror can keep the catalog next to the AppImages. `sha256` is optional,
+/// except when the catalog or the AppImage is served over plain http.
+#[derive(Deserialize)]
+struct Catalog {
+    #[serde(skip)]
+    source: String,
+    apps: Vec<CatalogApp>,
+}
+
+#[derive(Deserialize)]
+struct CatalogApp {
+    id: String,
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+    url: String,
+    sha256: Option<String>,
+    homepage: Option<String>,
+    license: Option<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+impl Catalog {
+    /// Finds an application by id.
+    fn find(&self, id: &str) -> Result<&CatalogApp, BackendError> {
+        self.apps.iter().find(|app| app.id == id).ok_or_else(|| {
+            BackendError::ParseError(format!("{} is not in the catalog {}", id, self.source))
+        })
+    }
+}
+
+/// AppImages installed by fi, stored in `$XDG_DATA_HOME/fi/appimages.json`.
+#[derive(Serialize, Deserialize, Default)]
+struct InstalledApps {
+    /// Keyed by catalog id.
+    #[serde(default)]
+    apps: BTreeMap<String, InstalledApp>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InstalledApp {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+    /// Path of the AppImage.
+    path: PathBuf,
+    /// Path of the `.desktop` entry.
+    desktop_entry: PathBuf,
+}
+
+impl InstalledApp {
+    fn to_result(&self, id: String) -> SearchResult {
+        SearchResult {
+            backend: "appimage".into(),
+            name: self.name.clone(),
+            identifier: id,
+            description: self.description.clone(),
+            version: Some(self.version.clone()),
+            installed: true,
+            installed_version: Some(self.version.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Returns `$XDG_DATA_HOME`, or `~/.local/share`.
+fn data_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
+
+/// Returns the catalog location from `FI_APPIMAGE_CATALOG`, or else
+/// `configured`, or else the default file if it exists.
+fn catalog_source(configured: Option<&str>) -> Option<String> {
+    if let Ok(source) = std::env::var(CATALOG_ENV)
+        && !source.is_empty()
+    {
+        return Some(source);
+    }
+
+    if let Some(source) = configured {
+        return Some(source.to_string());
+    }
+
+    let default = data_home()?.join("fi").join("appimage-catalog.json");
+    default.is_file().then(|| default.to_string_lossy().into_owned())
+}
+
+/// Returns `true` if `location` is an http(s) URL rather than a path.
+fn is_remote(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Fetches the catalog from a file or URL.
+async fn fetch_catalog(source: &str) -> Result<Catalog, BackendError> {
+    let contents = if is_remote(source) {
+        capture(
+            Command::new("curl")
+                .arg("--fail")
+                .arg("--silent")
+                .arg("--show-error")
+                .arg("--location")
+                .arg(source),
+        )
+        .await?
+    } else {
+        tokio::fs::read_to_string(source)
+            .await
+            .map_err(|e| io_error(Path::new(source), e))?
+    };
+
+    let mut catalog: Catalog = serde_json::from_str(&contents)
+        .map_err(|e| BackendError::ParseError(format!("{}: {}", source, e)))?;
+    catalog.source = source.to_string();
+    Ok(catalog)
+}
+
+/// Resolves an AppImage URL from the catalog against the catalog location.
+///
+/// For a remote catalog, `/path` is resolved against its scheme and host
+/// and other relative URLs against its directory. Filesystem paths are only
+/// used as such for local catalogs.
+fn resolve_url(source: &str, url: &str) -> String {
+    if is_remote(url) {
+        return url.to_string();
+    }
+
+    if is_remote(source) {
+        let (scheme, rest) = source.split_once("://").unwrap_or(("https", source));
+        if let Some(url) = url.strip_prefix("//") {
+            return format!("{}://{}", scheme, url);
+        }
+        if url.starts_with('/') {
+            let host = rest.split('/').next().unwrap_or(rest);
+            return format!("{}://{}{}", scheme, host, url);
+        }
+        return match rest.rsplit_once('/') {
+            Some((dir, _)) => format!("{}://{}/{}", scheme, dir, url),
+            None => format!("{}/{}", source, url),
+        };
+    }
+
+    Path::new(source)
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(url)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Returns the path of the installed apps record.
+fn installed_path() -> Result<PathBuf, BackendError> {
+    data_home()
+        .map(|dir| dir.join("fi").join("appimages.json"))
+        .ok_or_else(|| BackendError::Io("neither XDG_DATA_HOME nor HOME is set".into()))
+}
+
+/// Loads the installed apps record, empty if nothing was installed yet.
+async fn load_installed() -> Result<InstalledApps, BackendError> {
+    let path = installed_path()?;
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(InstalledApps::default()),
+        Err(e) => return Err(io_error(&path, e)),
+    };
+
+    serde_json::from_str(&contents)
+        .map_err(|e| BackendError::ParseError(format!("{}: {}", path.display(), e)))
+}
+
+/// Writes the installed apps record.
+async fn save_installed(installed: &InstalledApps) -> Result<(), BackendError> {
+    let path = installed_path()?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| io_error(dir, e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(installed)
+        .map_err(|e| BackendError::ParseError(e.to_string()))?;
+    tokio::fs::write(&path, contents).await.map_err(|e| io_error(&path, e))
+}
+
+/// Downloads an AppImage into `~/Applications`, makes it executable and
+/// creates its desktop entry.
+async fn install_app(source: &str, app: &CatalogApp) -> Result<InstalledApp, BackendError> {
+    check_id(&app.id)?;
+    let url = resolve_url(source, &app.url);
+
+    // Without TLS, only the checksum vouches for what was downloaded
+    if app.sha256.is_none() && (source.starts_with("http://") || url.starts_with("http://")) {
+        return Err(BackendError::Io(format!(
+            "{} has no sha256 in the catalog, which is required over plain http",
+            app.id
+        )));
+    }
+
+    let home = std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .ok_or_else(|| BackendError::Io("HOME is not set".into()))?;
+    let dir = home.join("Applications");
+    tokio::fs::create_dir_all(&dir).await.map_err(|e| io_error(&dir, e))?;
+
+    let path = dir.join(format!("{}.AppImage", app.id));
+    let partial = dir.join(format!("{}.AppImage.part", app.id));
+
+    println!("Downloading {} {}...", app.name, app.version);
+    if is_remote(&url) {
+        run(Command::new("curl")
+            .arg("--fail")
+            .arg("--location")
+            .arg("--progress-bar")
+            .arg("--output")
+            .arg(&partial)
+            .arg(&url))
+        .await?;
+    } else {
+        tokio::fs::copy(&url, &partial)
+            .await
+            .map_err(|e| io_error(Path::new(&url), e))?;
+    }
+
+    if let Some(expected) = &app.sha256
+        && let Err(e) = verify_checksum(&partial, expected).await
+    {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(e);
+    }
+
+    tokio::fs::set_permissions(&partial, std::fs::Permissions::from_mode(0o755))
+        .await
+        .map_err(|e| io_error(&partial, e))?;
+    tokio::fs::rename(&partial, &path)
+        .await
+        .map_err(|e| io_error(&path, e))?;
+
+    let desktop_entry = write_desktop_entry(app, &path).await?;
+
+    Ok(InstalledApp {
+        name: app.name.clone(),
+        version: app.version.clone(),
+        description: app.description.clone(),
+        path,
+        desktop_entry,
+    })
+}
+
+/// Checks that a catalog id can be used as a file name.
+///
+/// The id ends up in the AppImage and desktop entry paths, so one that could
+/// climb out of their directories is rejected.
+fn check_id(id: &str) -> Result<(), BackendError> {
+    if id.is_empty() || id.starts_with('.') || id.contains(['/', '\0']) || id.contains("..") {
+        return Err(BackendError::ParseError(format!(
+            "invalid AppImage id {:?} in the catalog",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// Checks a downloaded file against the SHA-256 checksum from the catalog.
+async fn verify_checksum(path: &Path, expected: &str) -> Result<(), BackendError> {
+    let stdout = capture(Command::new("sha256sum").arg(path)).await?;
+    let actual = stdout.split_whitespace().next().unwrap_or("");
+
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(BackendError::Io(format!(
+            "checksum mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected,
+            actual
+        )))
+    }
+}
+
+/// Writes the `.desktop` entry that makes the AppImage show up in the
+/// application menu.
+async fn write_desktop_entry(app: &CatalogApp, path: &Path) -> Result<PathBuf, BackendError> {
+    let dir = data_home()
+        .map(|dir| dir.join("applications"))
+        .ok_or_else(|| BackendError::Io("neither XDG_DATA_HOME nor HOME is set".into()))?;
+    tokio::fs::create_dir_all(&dir).await.map_err(|e| io_error(&dir, e))?;
+
+    let entry = dir.join(format!("fi-appimage-{}.desktop", app.id));
+    tokio::fs::write(&entry, desktop_entry(app, path))
+        .await
+        .map_err(|e| io_error(&entry, e))?;
+
+    Ok(entry)
+}
+
+/// Formats a `.desktop` entry for an AppImage.
+fn desktop_entry(app: &CatalogApp, path: &Path) -> String {
+    // Exec arguments are quoted, with `"`, `` ` ``, `$` and `\` escaped by a
+    // backslash, which itself has to be escaped in a desktop entry value
+    let mut exec = String::from("\"");
+    for c in desktop_value(&path.to_string_lossy()).chars() {
+        match c {
+            '"' | '`' | '$' => exec.push_str("\\\\"),
+            '\\' => exec.push_str("\\\\\\"),
+            _ => {}
+        }
+        exec.push(c);
+    }
+    exec.push('"');
+
+    let mut entry = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec={} %U\nTerminal=false\n",
+        desktop_value(&app.name), exec
+    );
+    if !app.description.is_empty() {
+        entry.push_str(&format!("Comment={}\n", desktop_value(&app.description)));
+    }
+    if !app.categories.is_empty() {
+        entry.push_str(&format!(
+            "Categories={};\n",
+            desktop_value(&app.categories.join(";"))
+        ));
+    }
+    entry.push_str(&format!("X-AppImage-Version={}\n", desktop_value(&app.version)));
+
+    entry
+}
+
+/// Makes a catalog value safe to put in a desktop entry, where a line break
+/// would start a new key, by turning control characters into spaces.
+fn desktop_value(s: &str) -> String {
+    s.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_remote_catalog_urls() {
+        let source = "https://example.com/apps/catalog.json";
+        assert_eq!(
+            resolve_url(source, "Foo.AppImage"),
+            "https://example.com/apps/Foo.AppImage"
+        );
+        assert_eq!(
+            resolve_url(source, "/downloads/Foo.AppImage"),
+            "https://example.com/downloads/Foo.AppImage"
+        );
+        assert_eq!(
+            resolve_url(source, "//cdn.example.org/Foo.AppImage"),
+            "https://cdn.example.org/Foo.AppImage"
+        );
+        assert_eq!(
+            resolve_url(source, "http://mirror.example.net/Foo.AppImage"),
+            "http://mirror.example.net/Foo.AppImage"
+        );
+        assert_eq!(
+            resolve_url("https://example.com", "Foo.AppImage"),
+            "https://example.com/Foo.AppImage"
+        );
+    }
+
+    #[test]
+    fn resolves_local_catalog_paths() {
+        let source = "/home/user/.local/share/fi/appimage-catalog.json";
+        assert_eq!(
+            resolve_url(source, "Foo.AppImage"),
+            "/home/user/.local/share/fi/Foo.AppImage"
+        );
+        assert_eq!(resolve_url(source, "/opt/apps/Foo.AppImage"), "/opt/apps/Foo.AppImage");
+    }
+}
@@ -0,0 +1,431 @@
+//! APT backend for searching and installing Debian packages.
+
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, run};
+use super::{mark_installed, root_command, Backend};
+
+/// Backend implementation for the APT package manager.
+///
+/// Packages installed for a foreign architecture, or for several (as
+/// `Multi-Arch: same` libraries like `libc6` are), carry their architecture
+/// and are acted on as `name:arch`.
+pub struct AptBackend;
+
+#[async_trait]
+impl Backend for AptBackend {
+    fn name(&self) -> &str { "apt" }
+
+    fn exists(&self) -> bool {
+        which::which("apt-get").is_ok() && which::which("dpkg-query").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching APT...");
+
+        let mut search = Command::new("apt-cache");
+        search.arg("search").arg("--").arg(query);
+
+        // List installed packages alongside the search to mark matches
+        let (output, installed) =
+            tokio::join!(capture(&mut search), self.list_installed(ProgressBar::hidden()));
+
+        let mut results = parse_apt_search(&output?);
+        mark_installed(&mut results, &installed.unwrap_or_default(), |r| &r.identifier);
+
+        pb.finish_with_message("APT search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing APT packages...");
+
+        let stdout = capture(
+            Command::new("dpkg-query")
+                .arg("--show")
+                .arg("--showformat=${db:Status-Abbrev}\t${binary:Package}\t${Version}\t${binary:Summary}\n"),
+        )
+        .await?;
+        let results = parse_dpkg_installed(&stdout);
+
+        pb.finish_with_message("APT listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking APT upgrades...");
+
+        // Uses the package lists from the last `apt-get update`, refreshing
+        // them needs root
+        let stdout = capture(Command::new("apt").arg("list").arg("--upgradable")).await?;
+        let results = parse_apt_upgradable(&stdout);
+
+        pb.finish_with_message("APT upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(
+            Command::new("apt-cache")
+                .arg("show")
+                .arg("--no-all-versions")
+                .arg(package_spec(pkg)),
+        )
+        .await?;
+
+        let info = parse_apt_show(&stdout);
+        if info.name.is_empty() {
+            return Err(BackendError::ParseError(format!(
+                "no package details for {} in apt-cache show output",
+                pkg.identifier
+            )));
+        }
+
+        Ok(info)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        let mut command = root_command("apt-get", assume_yes);
+        if assume_yes {
+            // Keep debconf from prompting for package configuration
+            command.env("DEBIAN_FRONTEND", "noninteractive");
+        }
+
+        run(command
+            .arg("install")
+            .arg("-y")
+            .args(pkgs.iter().map(package_spec)))
+        .await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("apt-get", false)
+            .arg("remove")
+            .arg("-y")
+            .args(pkgs.iter().map(package_spec)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("apt-get", false)
+            .arg("install")
+            .arg("--only-upgrade")
+            .arg("-y")
+            .args(pkgs.iter().map(package_spec)))
+        .await
+    }
+}
+
+/// Returns the spec APT commands take for a package, `name:arch` when the
+/// architecture is known.
+fn package_spec(pkg: &SearchResult) -> String {
+    match &pkg.arch {
+        Some(arch) => format!("{}:{}", pkg.identifier, arch),
+        None => pkg.identifier.clone(),
+    }
+}
+
+/// Parses `apt-cache search` output.
+///
+/// APT search output format: `name - description`
+/// Example: `ripgrep - Recursively searches directories for a regex pattern`
+fn parse_apt_search(s: &str) -> Vec<SearchResult> {
+    s.lines()
+        .filter_map(|line| {
+            let (name, description) = line.split_once(" - ")?;
+            let name = name.trim();
+
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return None;
+            }
+
+            Some(SearchResult {
+                backend: "apt".into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: description.trim().to_string(),
+                version: None, // apt-cache search doesn't show versions
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `dpkg-query --show` output.
+///
+/// `dpkg-query` is invoked with the format
+/// `${db:Status-Abbrev}\t${binary:Package}\t${Version}\t${binary:Summary}`.
+/// Only packages whose status is `ii` (installed and configured) are kept;
+/// removed packages with leftover configuration show up as `rc`.
+/// `${binary:Package}` has an `:arch` suffix when the architecture is needed
+/// to tell the package apart.
+/// Example: `ii \tlibc6:amd64\t2.39-0ubuntu8.3\tGNU C Library: Shared libraries`
+fn parse_dpkg_installed(s: &str) -> Vec<SearchResult> {
+    s.lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let status = parts.next()?.trim();
+            let name = parts.next()?.trim();
+            let version = parts.next()?.trim();
+            let summary = parts.next().unwrap_or("").trim();
+
+            if status != "ii" || name.is_empty() {
+                return None;
+            }
+            let (name, arch) = match name.split_once(':') {
+                Some((name, arch)) => (name, Some(arch.to_string())),
+                None => (name, None),
+            };
+
+            Some(SearchResult {
+                backend: "apt".into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: summary.to_string(),
+                version: Some(version.to_string()),
+                installed: true,
+                installed_version: Some(version.to_string()),
+                arch,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `apt list --upgradable` output.
+///
+/// Output format: `name/suites new-version arch [upgradable from: old-version]`,
+/// after a `Listing...` header.
+/// Example:
+/// ```text
+/// Listing...
+/// curl/noble-updates 8.5.0-2ubuntu10.4 amd64 [upgradable from: 8.5.0-2ubuntu10.3]
+/// ```
+fn parse_apt_upgradable(s: &str) -> Vec<SearchResult> {
+    s.lines()
+        .filter_map(|line| {
+            let (name, rest) = line.trim().split_once('/')?;
+            let mut fields = rest.split_whitespace();
+            let suite = fields.next()?;
+            let version = fields.next()?;
+            let arch = fields.next()?;
+
+            let installed_version = rest
+                .split_once("upgradable from: ")
+                .map(|(_, old)| old.trim_end_matches(']').trim().to_string());
+
+            Some(SearchResult {
+                backend: "apt".into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: format!("Update from {}", suite),
+                version: Some(version.to_string()),
+                installed: true,
+                installed_version,
+                arch: Some(arch.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `apt-cache show` output.
+///
+/// Output format: `Key: Value` fields, where continuation lines start with a
+/// space and a line holding only ` .` is an empty line. The first line of
+/// `Description` is the summary. Only the first record is parsed.
+/// Example:
+/// ```text
+/// Package: ripgrep
+/// Version: 14.1.0-1
+/// Installed-Size: 5443
+/// Depends: libc6 (>= 2.34), libgcc-s1 (>= 4.2), libpcre2-8-0 (>= 10.34)
+/// Homepage: https://github.com/BurntSushi/ripgrep
+/// Description: Recursively searches directories for a regex pattern
+///  ripgrep is a line oriented search tool that recursively searches your
+///  current directory for a regex pattern.
+///  .
+///  It is similar to grep.
+/// ```
+fn parse_apt_show(s: &str) -> PackageInfo {
+    let mut info = PackageInfo {
+        backend: "apt".into(),
+        ..PackageInfo::default()
+    };
+    let mut summary = "";
+    let mut body: Vec<&str> = Vec::new();
+    let mut last_key = "";
+
+    for line in s.lines() {
+        // An empty line ends the record
+        if line.trim().is_empty() {
+            if !info.name.is_empty() {
+                break;
+            }
+            continue;
+        }
+
+        if let Some(continuation) = line.strip_prefix(' ') {
+            if last_key == "Description" {
+                body.push(if continuation.trim() == "." { "" } else { continuation.trim() });
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        last_key = key;
+
+        match key {
+            "Package" => info.name = value.to_string(),
+            "Version" => info.version = Some(value.to_string()),
+            "Installed-Size" => info.size = Some(format!("{} KiB", value)),
+            "Homepage" => info.homepage = Some(value.to_string()),
+            "Section" => info.repository = Some(value.to_string()),
+            "Description" | "Description-en" => summary = value,
+            "Depends" => {
+                info.dependencies = value
+                    .split(',')
+                    .map(|dep| dep.trim().to_string())
+                    .filter(|dep| !dep.is_empty())
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    // Join wrapped lines into paragraphs, keeping the blank lines between them
+    let mut description = String::new();
+    for line in body {
+        if line.is_empty() {
+            description.push('\n');
+        } else {
+            if !description.is_empty() && !description.ends_with('\n') {
+                description.push(' ');
+            }
+            description.push_str(line);
+        }
+    }
+
+    info.description = if description.is_empty() {
+        summary.to_string()
+    } else {
+        description
+    };
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_apt_cache_search() {
+        let output = "\
+ripgrep - Recursively searches directories for a regex pattern
+ugrep - faster grep with an interactive query UI
+elpa-deadgrep - fast, friendly searching with ripgrep and Emacs
+";
+        let results = parse_apt_search(output);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].identifier, "ripgrep");
+        assert_eq!(results[0].description, "Recursively searches directories for a regex pattern");
+        assert_eq!(results[2].identifier, "elpa-deadgrep");
+        assert_eq!(results[2].description, "fast, friendly searching with ripgrep and Emacs");
+        assert!(results.iter().all(|r| !r.installed && r.version.is_none()));
+    }
+
+    #[test]
+    fn parses_dpkg_query_with_architectures() {
+        let output = "\
+ii \tbash\t5.2.21-2ubuntu4\tGNU Bourne Again SHell
+ii \tlibc6:amd64\t2.39-0ubuntu8.3\tGNU C Library: Shared libraries
+ii \tlibc6:i386\t2.39-0ubuntu8.3\tGNU C Library: Shared libraries
+rc \tlinux-image-6.8.0-40-generic\t6.8.0-40.40\tSigned kernel image generic
+iU \tcurl\t8.5.0-2ubuntu10.4\tcommand line tool for transferring data with URL syntax
+";
+        let results = parse_dpkg_installed(output);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].identifier, "bash");
+        assert_eq!(results[0].arch, None);
+        assert_eq!(results[0].installed_version.as_deref(), Some("5.2.21-2ubuntu4"));
+
+        assert_eq!(results[1].identifier, "libc6");
+        assert_eq!(results[1].arch.as_deref(), Some("amd64"));
+        assert_eq!(results[2].identifier, "libc6");
+        assert_eq!(results[2].arch.as_deref(), Some("i386"));
+        assert_eq!(package_spec(&results[2]), "libc6:i386");
+        assert!(results.iter().all(|r| r.installed));
+    }
+
+    #[test]
+    fn parses_apt_list_upgradable() {
+        let output = "\
+Listing...
+curl/noble-updates 8.5.0-2ubuntu10.5 amd64 [upgradable from: 8.5.0-2ubuntu10.4]
+libc6/noble-updates,noble-security 2.39-0ubuntu8.4 i386 [upgradable from: 2.39-0ubuntu8.3]
+";
+        let results = parse_apt_upgradable(output);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].identifier, "curl");
+        assert_eq!(results[0].version.as_deref(), Some("8.5.0-2ubuntu10.5"));
+        assert_eq!(results[0].installed_version.as_deref(), Some("8.5.0-2ubuntu10.4"));
+        assert_eq!(results[0].description, "Update from noble-updates");
+        assert_eq!(package_spec(&results[0]), "curl:amd64");
+        assert_eq!(results[1].description, "Update from noble-updates,noble-security");
+        assert_eq!(results[1].arch.as_deref(), Some("i386"));
+    }
+
+    #[test]
+    fn parses_apt_cache_show() {
+        let output = "\
+Package: ripgrep
+Architecture: amd64
+Version: 14.1.0-1
+Priority: optional
+Section: universe/utils
+Installed-Size: 5443
+Depends: libc6 (>= 2.34), libgcc-s1 (>= 4.2), libpcre2-8-0 (>= 10.34)
+Homepage: https://github.com/BurntSushi/ripgrep
+Description: Recursively searches directories for a regex pattern
+ ripgrep is a line oriented search tool that recursively searches your
+ current directory for a regex pattern.
+ .
+ It is similar to grep.
+
+Package: ripgrep
+Version: 13.0.0-4
+Description: an older record that is ignored
+";
+        let info = parse_apt_show(output);
+
+        assert_eq!(info.name, "ripgrep");
+        assert_eq!(info.version.as_deref(), Some("14.1.0-1"));
+        assert_eq!(info.size.as_deref(), Some("5443 KiB"));
+        assert_eq!(info.repository.as_deref(), Some("universe/utils"));
+        assert_eq!(info.homepage.as_deref(), Some("https://github.com/BurntSushi/ripgrep"));
+        assert_eq!(
+            info.dependencies,
+            ["libc6 (>= 2.34)", "libgcc-s1 (>= 4.2)", "libpcre2-8-0 (>= 10.34)"]
+        );
+        assert_eq!(
+            info.description,
+            "ripgrep is a line oriented search tool that recursively searches your current \
+             directory for a regex pattern.\nIt is similar to grep."
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! AUR backend for building Arch User Repository packages through an AUR
+//! helper (`yay` or `paru`).
+
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, capture_allowing, run};
+use super::pacman::{
+    check_full_upgrade, parse_pacman_info, parse_pacman_installed, parse_pacman_search,
+    parse_pacman_upgrades,
+};
+use super::{mark_installed, Backend};
+
+/// AUR helpers in order of preference. Both accept pacman's options plus
+/// `--aur` to restrict an operation to the AUR.
+const HELPERS: &[&str] = &["yay", "paru"];
+
+/// Backend implementation for the AUR, driven by `yay` or `paru`.
+pub struct AurBackend;
+
+impl AurBackend {
+    /// Returns the first AUR helper found on the system.
+    fn helper(&self) -> Option<&'static str> {
+        HELPERS.iter().copied().find(|helper| which::which(helper).is_ok())
+    }
+
+    /// Builds a command running the AUR helper.
+    ///
+    /// Helpers call sudo themselves when needed, and refuse to build packages
+    /// as root, so they are never run through [`super::root_command`].
+    fn command(&self) -> Result<Command, BackendError> {
+        let helper = self
+            .helper()
+            .ok_or_else(|| BackendError::CommandNotFound(HELPERS.join(" or ")))?;
+        Ok(Command::new(helper))
+    }
+}
+
+#[async_trait]
+impl Backend for AurBackend {
+    fn name(&self) -> &str { "aur" }
+
+    fn exists(&self) -> bool {
+        which::which("pacman").is_ok() && self.helper().is_some()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching AUR...");
+
+        // Helpers exit with 1 when nothing matches
+        let mut search = self.command()?;
+        search.arg("-Ss").arg("--aur").arg(query);
+
+        // The installed tag isn't reliable across helpers, so list foreign
+        // packages alongside the search to mark matches
+        let (output, installed) = tokio::join!(
+            capture_allowing(&mut search, &[1]),
+            self.list_installed(ProgressBar::hidden())
+        );
+
+        let mut results = parse_pacman_search(&output?, "aur");
+        mark_installed(&mut results, &installed.unwrap_or_default(), |r| &r.identifier);
+
+        pb.finish_with_message("AUR search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing AUR packages...");
+
+        // Foreign packages, i.e. those not in any sync repository
+        let stdout = capture(Command::new("pacman").arg("-Qm")).await?;
+        let results = parse_pacman_installed(&stdout, "aur");
+
+        pb.finish_with_message("AUR listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking AUR upgrades...");
+
+        // Queries the AUR for newer versions of foreign packages. Exits with
+        // 1 when nothing is upgradable.
+        let mut check = self.command()?;
+        check.arg("-Qu").arg("--aur");
+        let stdout = capture_allowing(&mut check, &[1]).await?;
+        let results = parse_pacman_upgrades(&stdout, "aur");
+
+        pb.finish_with_message("AUR upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(self.command()?.arg("-Si").arg("--aur").arg(&pkg.identifier)).await?;
+
+        let info = parse_pacman_info(&stdout, "aur");
+        if info.name.is_empty() {
+            return Err(BackendError::ParseError(format!(
+                "no package details for {} in AUR helper output",
+                pkg.identifier
+            )));
+        }
+
+        Ok(info)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        let mut command = self.command()?;
+        command.arg("-S").arg("--aur");
+
+        // Without --yes the helper offers to review the PKGBUILDs before
+        // building, which is the point of installing from the AUR
+        if assume_yes {
+            command.arg("--noconfirm");
+        }
+
+        run(command.args(pkgs.iter().map(|p| &p.identifier))).await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(self
+            .command()?
+            .arg("-R")
+            .arg("--noconfirm")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        let pending = self.list_upgrades(ProgressBar::hidden()).await?;
+        check_full_upgrade(pkgs, &pending)?;
+
+        // Like pacman, all AUR packages are upgraded together
+        run(self.command()?.arg("-Sua")).await
+    }
+}
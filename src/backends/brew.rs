@@ -0,0 +1,308 @@
+//! Homebrew backend for searching and installing formulae on Linux.
+
+use futures::future::join_all;
+use serde::Deserialize;
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, run};
+use super::Backend;
+
+/// Maximum number of search matches looked up with `brew info`. Broad
+/// queries match hundreds of formulae and the lookup time grows with each.
+const MAX_INFO_LOOKUPS: usize = 100;
+
+/// Number of formulae looked up per `brew info` call. A single name brew
+/// can't resolve fails the whole call, so this limits what is lost.
+const INFO_CHUNK_SIZE: usize = 20;
+
+/// Backend implementation for the Homebrew package manager.
+pub struct BrewBackend;
+
+#[async_trait]
+impl Backend for BrewBackend {
+    fn name(&self) -> &str { "brew" }
+
+    fn exists(&self) -> bool {
+        which::which("brew").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching Homebrew...");
+
+        // `brew search` only prints names, versions and descriptions come
+        // from `brew info`. Casks are macOS only.
+        let mut search = Command::new("brew");
+        search.arg("search").arg("--formula").arg(query);
+
+        let names = match capture(&mut search).await {
+            Ok(stdout) => parse_brew_search(&stdout),
+            // brew exits with an error when nothing matches
+            Err(BackendError::CommandFailed { stderr, .. })
+                if stderr.contains("No formulae") => vec![],
+            Err(e) => return Err(e),
+        };
+
+        if names.is_empty() {
+            pb.finish_with_message("Homebrew search done");
+            return Ok(vec![]);
+        }
+
+        pb.set_message("Fetching Homebrew formula details...");
+        let (detailed, rest) = names.split_at(names.len().min(MAX_INFO_LOOKUPS));
+
+        let chunks: Vec<&[String]> = detailed.chunks(INFO_CHUNK_SIZE).collect();
+        let lookups = join_all(chunks.iter().map(|chunk| async move {
+            let stdout = capture(brew_info().arg("--formula").args(*chunk)).await?;
+            parse_brew_formulae(&stdout)
+        }))
+        .await;
+
+        // Matches whose details couldn't be looked up are still listed
+        let mut results = Vec::new();
+        for (chunk, lookup) in chunks.iter().zip(lookups) {
+            match lookup {
+                Ok(formulae) => results.extend(formulae),
+                Err(_) => results.extend(chunk.iter().map(|name| name_only(name))),
+            }
+        }
+        results.extend(rest.iter().map(|name| name_only(name)));
+
+        pb.finish_with_message("Homebrew search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Homebrew formulae...");
+
+        let stdout = capture(brew_info().arg("--installed")).await?;
+        let results = parse_brew_formulae(&stdout)?
+            .into_iter()
+            .map(|mut r| {
+                r.version = r.installed_version.clone();
+                r
+            })
+            .collect();
+
+        pb.finish_with_message("Homebrew listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Homebrew upgrades...");
+
+        let stdout = capture(
+            Command::new("brew")
+                .arg("outdated")
+                .arg("--formula")
+                .arg("--json=v2"),
+        )
+        .await?;
+        let results = parse_brew_outdated(&stdout)?;
+
+        pb.finish_with_message("Homebrew upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(brew_info().arg("--formula").arg(&pkg.identifier)).await?;
+
+        parse_brew_info(&stdout)?.ok_or_else(|| {
+            BackendError::ParseError(format!(
+                "no formula details for {} in brew info output",
+                pkg.identifier
+            ))
+        })
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], _assume_yes: bool) -> Result<(), BackendError> {
+        // brew never prompts, and refuses to run as root
+        run(Command::new("brew")
+            .arg("install")
+            .arg("--formula")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(Command::new("brew")
+            .arg("uninstall")
+            .arg("--formula")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(Command::new("brew")
+            .arg("upgrade")
+            .arg("--formula")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+}
+
+/// Returns a search result for a formula known only by name.
+fn name_only(name: &str) -> SearchResult {
+    SearchResult {
+        backend: "brew".into(),
+        name: name.to_string(),
+        identifier: name.to_string(),
+        description: "No description available".into(),
+        version: None,
+        ..Default::default()
+    }
+}
+
+/// Builds a `brew info` command with JSON output.
+fn brew_info() -> Command {
+    let mut command = Command::new("brew");
+    command.arg("info").arg("--json=v2");
+    command
+}
+
+/// The document printed by `brew info --json=v2`.
+#[derive(Deserialize)]
+struct BrewInfo {
+    #[serde(default)]
+    formulae: Vec<BrewFormula>,
+}
+
+#[derive(Deserialize)]
+struct BrewFormula {
+    name: String,
+    desc: Option<String>,
+    license: Option<String>,
+    homepage: Option<String>,
+    tap: Option<String>,
+    #[serde(default)]
+    versions: BrewVersions,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    /// One entry per installed version, empty if not installed.
+    #[serde(default)]
+    installed: Vec<BrewInstalled>,
+}
+
+#[derive(Deserialize, Default)]
+struct BrewVersions {
+    stable: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BrewInstalled {
+    version: String,
+}
+
+/// The document printed by `brew outdated --json=v2`.
+#[derive(Deserialize)]
+struct BrewOutdated {
+    #[serde(default)]
+    formulae: Vec<BrewOutdatedFormula>,
+}
+
+#[derive(Deserialize)]
+struct BrewOutdatedFormula {
+    name: String,
+    #[serde(default)]
+    installed_versions: Vec<String>,
+    current_version: String,
+    #[serde(default)]
+    pinned: bool,
+}
+
+/// Parses `brew search` output.
+///
+/// Output format: one formula name per line when not writing to a terminal,
+/// possibly under `==> Formulae` headings.
+fn parse_brew_search(s: &str) -> Vec<String> {
+    s.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with("==>"))
+        .flat_map(|line| line.split_whitespace())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Deserializes `brew info --json=v2` output.
+fn parse_brew_json(s: &str) -> Result<BrewInfo, BackendError> {
+    serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("brew info JSON output: {}", e)))
+}
+
+/// Converts `brew info --json=v2` output into search results.
+///
+/// The version is the latest stable version, and the installed version the
+/// most recent of the installed ones.
+fn parse_brew_formulae(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    Ok(parse_brew_json(s)?
+        .formulae
+        .into_iter()
+        .map(|formula| {
+            let installed_version = formula.installed.last().map(|i| i.version.clone());
+
+            SearchResult {
+                backend: "brew".into(),
+                name: formula.name.clone(),
+                identifier: formula.name,
+                description: formula
+                    .desc
+                    .unwrap_or_else(|| "No description available".to_string()),
+                version: formula.versions.stable,
+                installed: installed_version.is_some(),
+                installed_version,
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+/// Converts the first formula in `brew info --json=v2` output into package
+/// details, or `None` if there is none.
+fn parse_brew_info(s: &str) -> Result<Option<PackageInfo>, BackendError> {
+    let Some(formula) = parse_brew_json(s)?.formulae.into_iter().next() else {
+        return Ok(None);
+    };
+
+    Ok(Some(PackageInfo {
+        backend: "brew".into(),
+        name: formula.name,
+        version: formula.versions.stable,
+        description: formula.desc.unwrap_or_default(),
+        license: formula.license,
+        homepage: formula.homepage,
+        repository: formula.tap,
+        size: None,
+        dependencies: formula.dependencies,
+    }))
+}
+
+/// Parses `brew outdated --json=v2` output.
+///
+/// Pinned formulae are skipped since `brew upgrade` leaves them alone.
+fn parse_brew_outdated(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let outdated: BrewOutdated = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("brew outdated JSON output: {}", e)))?;
+
+    Ok(outdated
+        .formulae
+        .into_iter()
+        .filter(|formula| !formula.pinned)
+        .map(|formula| SearchResult {
+            backend: "brew".into(),
+            name: formula.name.clone(),
+            identifier: formula.name,
+            description: "Update from Homebrew".into(),
+            version: Some(formula.current_version),
+            installed: true,
+            installed_version: formula.installed_versions.last().cloned(),
+            ..Default::default()
+        })
+        .collect())
+}
@@ -1,53 +1,300 @@
 //! Cargo backend for searching and installing Rust crates.
 
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::PathBuf;
+
+use futures::future::join_all;
+use serde::Deserialize;
 use tokio::process::Command;
 use indicatif::ProgressBar;
 use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
 use crate::models::result::SearchResult;
-use super::Backend;
+use crate::utils::command::{capture, run};
+use crate::utils::version::is_newer;
+use super::{collect_lookups, io_error, mark_installed, Backend};
 
 /// Backend implementation for the Cargo package manager.
 pub struct CargoBackend;
 
 #[async_trait]
 impl Backend for CargoBackend {
-    fn name(&self) -> &'static str { "cargo" }
+    fn name(&self) -> &str { "cargo" }
 
     fn exists(&self) -> bool {
         which::which("cargo").is_ok()
     }
 
-    async fn search(&self, query: &str, pb: ProgressBar) -> Vec<SearchResult> {
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
         pb.set_message("Searching Cargo...");
 
-        let output = Command::new("cargo")
-            .arg("search")
-            .arg(query)
-            .output()
-            .await;
-
-        let results = if let Ok(out) = output {
-            parse_cargo(&String::from_utf8_lossy(&out.stdout))
-        } else {
-            vec![]
-        };
+        let stdout = capture(Command::new("cargo").arg("search").arg(query)).await?;
+        let mut results = parse_cargo(&stdout);
+
+        let installed = installed_crates(false).await.unwrap_or_default();
+        mark_installed(&mut results, &installed, |r| &r.identifier);
 
         pb.finish_with_message("Cargo search done");
-        results
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Cargo crates...");
+
+        let results = installed_crates(false).await?;
+
+        pb.finish_with_message("Cargo listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Cargo upgrades...");
+
+        let installed = installed_crates(true).await?;
+        let latest = join_all(installed.iter().map(|c| latest_version(&c.identifier))).await;
+
+        let latest = collect_lookups(latest)?;
+
+        let results = installed
+            .into_iter()
+            .zip(latest)
+            .filter_map(|(mut krate, latest)| {
+                let latest = latest??;
+                if !is_newer(&latest, krate.installed_version.as_deref()?) {
+                    return None;
+                }
+                krate.version = Some(latest);
+                Some(krate)
+            })
+            .collect();
+
+        pb.finish_with_message("Cargo upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(
+            Command::new("cargo")
+                .arg("info")
+                .arg("--quiet")
+                .arg(&pkg.identifier),
+        )
+        .await?;
+
+        Ok(parse_cargo_info(&stdout))
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], _assume_yes: bool) -> Result<(), BackendError> {
+        // cargo install never prompts
+        run(Command::new("cargo")
+            .arg("install")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
     }
 
-    async fn install(&self, pkg: &SearchResult) -> Result<(), String> {
-        let status = Command::new("cargo")
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(Command::new("cargo")
+            .arg("uninstall")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        // `cargo install` replaces an installed crate when a newer version exists
+        run(Command::new("cargo")
             .arg("install")
-            .arg(&pkg.identifier)
-            .status()
-            .await;
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+}
+
+/// Looks up the latest published version of a crate on crates.io.
+///
+/// Returns `Ok(None)` if the crate isn't published there.
+async fn latest_version(name: &str) -> Result<Option<String>, BackendError> {
+    let stdout = capture(
+        Command::new("cargo")
+            .arg("search")
+            .arg("--limit")
+            .arg("1")
+            .arg(name),
+    )
+    .await?;
+
+    Ok(parse_cargo(&stdout)
+        .into_iter()
+        .find(|r| r.identifier == name)
+        .and_then(|r| r.version))
+}
+
+/// Cargo's record of installed crates, stored in `$CARGO_HOME/.crates2.json`.
+#[derive(Deserialize)]
+struct CratesManifest {
+    /// Keyed by `name version (source)`.
+    installs: HashMap<String, CrateInstall>,
+}
+
+#[derive(Deserialize)]
+struct CrateInstall {
+    #[serde(default)]
+    bins: Vec<String>,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    all_features: bool,
+    profile: Option<String>,
+    target: Option<String>,
+}
+
+/// Reads the installed crates from Cargo's install manifest.
+///
+/// Unlike `cargo install --list`, the manifest records where each crate
+/// came from and how it was built. With `registry_only` set, crates
+/// installed from git or a local path are skipped. A missing or empty
+/// manifest means nothing is installed.
+async fn installed_crates(registry_only: bool) -> Result<Vec<SearchResult>, BackendError> {
+    let Some(cargo_home) = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")))
+    else {
+        return Ok(vec![]);
+    };
+
+    let path = cargo_home.join(".crates2.json");
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(io_error(&path, e)),
+    };
+    if contents.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let manifest: CratesManifest = serde_json::from_str(&contents)
+        .map_err(|e| BackendError::ParseError(format!("{}: {}", path.display(), e)))?;
+
+    Ok(parse_crates_manifest(&manifest, registry_only))
+}
+
+/// Converts the install manifest entries into installed results.
+fn parse_crates_manifest(manifest: &CratesManifest, registry_only: bool) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = manifest
+        .installs
+        .iter()
+        .filter(|(key, _)| {
+            !registry_only || key.contains("(registry+") || key.contains("(sparse+")
+        })
+        .filter_map(|(key, install)| {
+            let mut parts = key.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next().map(|v| v.to_string());
 
-        if let Ok(s) = status {
-            if s.success() { return Ok(()); }
+            let mut metadata = BTreeMap::new();
+            if let Some(source) = parts
+                .next()
+                .and_then(|s| s.strip_prefix('('))
+                .and_then(|s| s.strip_suffix(')'))
+            {
+                metadata.insert("source".into(), source.to_string());
+            }
+            if install.all_features {
+                metadata.insert("features".into(), "all".into());
+            } else if !install.features.is_empty() {
+                metadata.insert("features".into(), install.features.join(", "));
+            }
+            if let Some(profile) = &install.profile {
+                metadata.insert("profile".into(), profile.clone());
+            }
+            if let Some(target) = &install.target {
+                metadata.insert("target".into(), target.clone());
+            }
+
+            Some(SearchResult {
+                backend: "cargo".into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: format!("Binaries: {}", install.bins.join(", ")),
+                version: version.clone(),
+                installed: true,
+                installed_version: version,
+                metadata,
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    results
+}
+
+/// Parses `cargo info` output.
+///
+/// The first line holds the crate name and its keywords, followed by the
+/// description and then `key: value` lines. Features and dependencies are
+/// listed on indented lines below their heading.
+/// Example:
+/// ```text
+/// ripgrep #grep #search
+/// ripgrep is a line-oriented search tool.
+/// version: 14.1.1
+/// license: Unlicense OR MIT
+/// homepage: https://github.com/BurntSushi/ripgrep
+/// dependencies:
+///   anyhow@1.0.75
+/// ```
+fn parse_cargo_info(s: &str) -> PackageInfo {
+    let mut info = PackageInfo {
+        backend: "cargo".into(),
+        ..PackageInfo::default()
+    };
+    let mut lines = s.lines();
+    let mut section = "";
+
+    if let Some(title) = lines.next() {
+        info.name = title.split_whitespace().next().unwrap_or("").to_string();
+    }
+
+    for line in lines {
+        // Indented lines belong to the current section
+        if line.starts_with(char::is_whitespace) {
+            if section == "dependencies" {
+                info.dependencies.push(line.trim().to_string());
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(": ").or_else(|| {
+            line.strip_suffix(':').map(|key| (key, ""))
+        }) else {
+            // Lines before the first key make up the description
+            if info.version.is_none() {
+                if !info.description.is_empty() {
+                    info.description.push('\n');
+                }
+                info.description.push_str(line.trim());
+            }
+            continue;
+        };
+
+        section = key;
+        let value = value.trim().to_string();
+
+        match key {
+            "version" => info.version = Some(value),
+            "license" => info.license = Some(value),
+            "homepage" => info.homepage = Some(value),
+            "repository" => info.repository = Some(value),
+            _ => {}
         }
-        Err("Cargo install failed".into())
     }
+
+    info
 }
 
 /// Parses Cargo search output.
@@ -69,9 +316,7 @@ fn parse_cargo(s: &str) -> Vec<SearchResult> {
             let trimmed = line.trim();
             
             // Find the equals sign
-            let Some(eq_pos) = trimmed.find(" = ") else {
-                return None;
-            };
+            let eq_pos = trimmed.find(" = ")?;
             
             let name = trimmed[..eq_pos].trim();
             let rest = &trimmed[eq_pos + 3..]; // Skip " = "
@@ -97,7 +342,7 @@ fn parse_cargo(s: &str) -> Vec<SearchResult> {
             } else {
                 // No quoted version, try to parse anyway
                 let parts: Vec<&str> = rest.split('#').collect();
-                let version = parts.get(0).map(|s| s.trim()).filter(|s| !s.is_empty());
+                let version = parts.first().map(|s| s.trim()).filter(|s| !s.is_empty());
                 let description = parts.get(1).map(|s| s.trim()).unwrap_or("").to_string();
                 (version.map(|s| s.to_string()), description)
             };
@@ -116,8 +361,102 @@ fn parse_cargo(s: &str) -> Vec<SearchResult> {
                     description
                 },
                 version,
+                ..Default::default()
             })
         })
         .collect()
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+  "installs": {
+    "ripgrep 14.1.0 (registry+https://github.com/rust-lang/crates.io-index)": {
+      "version_req": null,
+      "bins": ["rg"],
+      "features": ["pcre2"],
+      "all_features": false,
+      "no_default_features": false,
+      "profile": "release",
+      "target": "x86_64-unknown-linux-gnu",
+      "rustc": "rustc 1.80.0 (051478957 2024-07-21)"
+    },
+    "bat 0.24.0 (sparse+https://index.crates.io/)": {
+      "version_req": null,
+      "bins": ["bat"],
+      "features": [],
+      "all_features": true,
+      "no_default_features": false,
+      "profile": "release",
+      "target": "x86_64-unknown-linux-gnu",
+      "rustc": "rustc 1.80.0 (051478957 2024-07-21)"
+    },
+    "jj-cli 0.20.0 (git+https://github.com/martinvonz/jj#0cd1de2a)": {
+      "version_req": null,
+      "bins": ["jj"],
+      "features": [],
+      "all_features": false,
+      "no_default_features": false,
+      "profile": "release",
+      "target": "x86_64-unknown-linux-gnu",
+      "rustc": "rustc 1.80.0 (051478957 2024-07-21)"
+    },
+    "mytool 0.1.0 (path+file:///home/user/src/mytool)": {
+      "version_req": null,
+      "bins": ["mytool", "mytool-helper"],
+      "features": [],
+      "all_features": false,
+      "no_default_features": false,
+      "profile": "dev",
+      "target": "x86_64-unknown-linux-gnu",
+      "rustc": "rustc 1.80.0 (051478957 2024-07-21)"
+    }
+  }
+}"#;
+
+    fn manifest() -> CratesManifest {
+        serde_json::from_str(MANIFEST).unwrap()
+    }
+
+    #[test]
+    fn parses_every_install_source() {
+        let results = parse_crates_manifest(&manifest(), false);
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["bat", "jj-cli", "mytool", "ripgrep"]);
+
+        let ripgrep = &results[3];
+        assert_eq!(ripgrep.installed_version.as_deref(), Some("14.1.0"));
+        assert_eq!(ripgrep.description, "Binaries: rg");
+        assert_eq!(
+            ripgrep.metadata["source"],
+            "registry+https://github.com/rust-lang/crates.io-index"
+        );
+        assert_eq!(ripgrep.metadata["features"], "pcre2");
+        assert_eq!(ripgrep.metadata["profile"], "release");
+        assert_eq!(ripgrep.metadata["target"], "x86_64-unknown-linux-gnu");
+
+        assert_eq!(results[1].metadata["source"], "git+https://github.com/martinvonz/jj#0cd1de2a");
+        assert!(!results[1].metadata.contains_key("features"));
+
+        let mytool = &results[2];
+        assert_eq!(mytool.metadata["source"], "path+file:///home/user/src/mytool");
+        assert_eq!(mytool.metadata["profile"], "dev");
+        assert_eq!(mytool.description, "Binaries: mytool, mytool-helper");
+    }
+
+    #[test]
+    fn reports_all_features() {
+        let results = parse_crates_manifest(&manifest(), false);
+        assert_eq!(results[0].name, "bat");
+        assert_eq!(results[0].metadata["features"], "all");
+    }
+
+    #[test]
+    fn registry_only_skips_git_and_path_installs() {
+        let results = parse_crates_manifest(&manifest(), true);
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["bat", "ripgrep"]);
+    }
+}
@@ -3,54 +3,145 @@
 use tokio::process::Command;
 use indicatif::ProgressBar;
 use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
 use crate::models::result::SearchResult;
-use super::Backend;
+use crate::utils::command::{capture, capture_allowing, run};
+use super::{mark_installed_by, root_command, Backend};
 
 /// Backend implementation for the DNF package manager.
+///
+/// Results keep the architecture apart from the name, and packages are
+/// installed, removed and upgraded as `name.arch`, so multilib systems act on
+/// exactly the build the user picked.
 pub struct DnfBackend;
 
 #[async_trait]
 impl Backend for DnfBackend {
-    fn name(&self) -> &'static str { "dnf" }
+    fn name(&self) -> &str { "dnf" }
 
     fn exists(&self) -> bool {
         which::which("dnf").is_ok()
     }
 
-    async fn search(&self, query: &str, pb: ProgressBar) -> Vec<SearchResult> {
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
         pb.set_message("Searching DNF...");
 
-        let output = Command::new("dnf")
+        let mut search = Command::new("dnf");
+        search
             .arg("search")
             .arg("--assumeyes")
             .arg("--setopt=assumeyes=True")
-            .arg(query)
-            .output()
-            .await;
-
-        let results = if let Ok(out) = output {
-            parse_dnf(&String::from_utf8_lossy(&out.stdout))
-        } else {
-            vec![]
+            .arg(query);
+
+        // List installed packages alongside the search to mark matches
+        let (output, installed) =
+            tokio::join!(capture(&mut search), self.list_installed(ProgressBar::hidden()));
+
+        let mut results = match output {
+            Ok(stdout) => parse_dnf(&stdout),
+            // Older DNF versions exit with an error when nothing matches
+            Err(BackendError::CommandFailed { stderr, .. })
+                if stderr.contains("No matches found") => vec![],
+            Err(e) => return Err(e),
         };
 
+        // Only the installed architecture is marked
+        mark_installed_by(&mut results, &installed.unwrap_or_default(), same_build);
+
         pb.finish_with_message("DNF search done");
-        results
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing DNF packages...");
+
+        let stdout = capture(Command::new("dnf").arg("list").arg("--installed")).await?;
+        let results = parse_dnf_installed(&stdout);
+
+        pb.finish_with_message("DNF listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking DNF upgrades...");
+
+        // check-upgrade exits with 100 when upgrades are available
+        let mut check = Command::new("dnf");
+        check.arg("check-upgrade");
+
+        // The installed versions are not part of check-upgrade output
+        let (output, installed) = tokio::join!(
+            capture_allowing(&mut check, &[100]),
+            self.list_installed(ProgressBar::hidden())
+        );
+
+        let mut results = parse_dnf_upgrades(&output?);
+        mark_installed_by(&mut results, &installed.unwrap_or_default(), same_build);
+
+        pb.finish_with_message("DNF upgrade check done");
+        Ok(results)
     }
 
-    async fn install(&self, pkg: &SearchResult) -> Result<(), String> {
-        let status = Command::new("sudo")
-            .arg("dnf")
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let mut info = Command::new("dnf");
+        info.arg("info").arg("--quiet").arg(package_spec(pkg));
+
+        // `dnf info` doesn't list dependencies
+        let mut requires = Command::new("dnf");
+        requires
+            .arg("repoquery")
+            .arg("--requires")
+            .arg("--quiet")
+            .arg(package_spec(pkg));
+
+        let (info, requires) = tokio::join!(capture(&mut info), capture(&mut requires));
+
+        let mut info = parse_dnf_info(&info?);
+        if info.name.is_empty() {
+            return Err(BackendError::ParseError(format!(
+                "no package details for {} in dnf info output",
+                pkg.identifier
+            )));
+        }
+
+        if let Ok(stdout) = requires {
+            info.dependencies = stdout
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect();
+        }
+
+        Ok(info)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        run(root_command("dnf", assume_yes)
             .arg("install")
             .arg("-y")
-            .arg(&pkg.identifier)
-            .status()
-            .await;
+            .args(pkgs.iter().map(package_spec)))
+        .await
+    }
 
-        if let Ok(s) = status {
-            if s.success() { return Ok(()); }
-        }
-        Err("DNF install failed".into())
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("dnf", false)
+            .arg("remove")
+            .arg("-y")
+            .args(pkgs.iter().map(package_spec)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("dnf", false)
+            .arg("upgrade")
+            .arg("-y")
+            .args(pkgs.iter().map(package_spec)))
+        .await
     }
 }
 
@@ -83,33 +174,277 @@ fn parse_dnf(s: &str) -> Vec<SearchResult> {
                 return None;
             }
 
-            // Extract package name and architecture
-            // Format: PackageName.arch (e.g., "test.x86_64" or "test.noarch")
-            let (pkg_name, arch) = if let Some(dot_pos) = full_name.rfind('.') {
-                let (name, arch_part) = full_name.split_at(dot_pos);
-                (name, Some(&arch_part[1..])) // Skip the dot
-            } else {
-                (full_name, None)
-            };
+            let (pkg_name, arch) = split_arch(full_name);
 
-            // Use just the package name as identifier (DNF installs by name, not arch)
+            // The architecture is added back when the package is installed
             let identifier = pkg_name.to_string();
-            
-            // Create display name with architecture if present
-            let display_name = if let Some(arch) = arch {
-                format!("{} ({})", pkg_name, arch)
-            } else {
-                pkg_name.to_string()
-            };
 
             Some(SearchResult {
                 backend: "dnf".into(),
-                name: display_name,
+                name: pkg_name.to_string(),
                 identifier,
                 description: description.to_string(),
                 version: None, // DNF search doesn't show versions
+                arch: arch.map(|a| a.to_string()),
+                ..Default::default()
             })
         })
         .collect()
 }
 
+/// Parses `dnf list --installed` output.
+///
+/// See [`parse_dnf_columns`] for the format.
+fn parse_dnf_installed(s: &str) -> Vec<SearchResult> {
+    parse_dnf_columns(s)
+        .into_iter()
+        .map(|(full_name, version, repo)| {
+            let (pkg_name, arch) = split_arch(full_name);
+
+            let repo = repo.trim_start_matches('@');
+
+            SearchResult {
+                backend: "dnf".into(),
+                name: pkg_name.to_string(),
+                identifier: pkg_name.to_string(),
+                description: format!("Installed from {}", repo),
+                version: Some(version.to_string()),
+                installed: true,
+                installed_version: Some(version.to_string()),
+                origin: Some(repo.to_string()),
+                arch: arch.map(|a| a.to_string()),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Parses `dnf check-upgrade` output.
+///
+/// See [`parse_dnf_columns`] for the format. The installed version is not
+/// part of this output and is left unset.
+fn parse_dnf_upgrades(s: &str) -> Vec<SearchResult> {
+    parse_dnf_columns(s)
+        .into_iter()
+        .map(|(full_name, version, repo)| {
+            let (pkg_name, arch) = split_arch(full_name);
+
+            SearchResult {
+                backend: "dnf".into(),
+                name: pkg_name.to_string(),
+                identifier: pkg_name.to_string(),
+                description: format!("Update from {}", repo),
+                version: Some(version.to_string()),
+                installed: true,
+                origin: Some(repo.to_string()),
+                arch: arch.map(|a| a.to_string()),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Parses the column layout shared by `dnf list` and `dnf check-upgrade`.
+///
+/// Output format: `PackageName.arch  version  repository`, with whitespace
+/// aligned columns. Long package names are wrapped so that the version and
+/// repository continue on the next line.
+/// Example: `python3.x86_64   3.12.2-2.fc40   @updates`
+///
+/// Returns `(full_name, version, repository)` tuples. Parsing stops at the
+/// "Obsoleting Packages" section, which lists replaced packages.
+fn parse_dnf_columns(s: &str) -> Vec<(&str, &str, &str)> {
+    let mut rows = Vec::new();
+    let mut wrapped_name: Option<&str> = None;
+
+    for line in s.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("Obsoleting") {
+            break;
+        }
+
+        // Skip header lines and empty lines
+        if trimmed.is_empty()
+            || trimmed.starts_with("Installed")
+            || trimmed.starts_with("Last metadata")
+            || trimmed.starts_with("Updating")
+            || trimmed.starts_with("Repositories")
+        {
+            continue;
+        }
+
+        let fields: Vec<&str> = trimmed.split_whitespace().collect();
+
+        let row = match (wrapped_name.take(), fields.as_slice()) {
+            (None, [name]) => {
+                wrapped_name = Some(name);
+                continue;
+            }
+            (None, [name, version, repo]) => (*name, *version, *repo),
+            (Some(name), [version, repo]) => (name, *version, *repo),
+            _ => continue,
+        };
+
+        rows.push(row);
+    }
+
+    rows
+}
+
+/// Parses `dnf info` output.
+///
+/// Output format: `Key : Value` lines, where continuation lines of a value
+/// (e.g. the description) have an empty key. Only the first package is
+/// parsed when several versions or architectures are listed.
+/// Example:
+/// ```text
+/// Name         : bash
+/// Version      : 5.2.26
+/// Release      : 3.fc40
+/// License      : GPL-3.0-or-later
+/// Description  : The GNU Bourne Again shell (Bash) is a shell and command
+///              : language interpreter.
+/// ```
+fn parse_dnf_info(s: &str) -> PackageInfo {
+    let mut info = PackageInfo {
+        backend: "dnf".into(),
+        ..PackageInfo::default()
+    };
+    let mut release: Option<&str> = None;
+    let mut summary = "";
+    let mut last_key = "";
+
+    for line in s.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        match key {
+            "Name" if !info.name.is_empty() => break,
+            "Name" => info.name = value.to_string(),
+            "Version" => info.version = Some(value.to_string()),
+            "Release" => release = Some(value),
+            "Size" | "Installed size" => info.size = Some(value.to_string()),
+            "Repository" | "Repo" => info.repository = Some(value.to_string()),
+            "From repo" => info.repository = Some(value.to_string()),
+            "Summary" => summary = value,
+            "URL" => info.homepage = Some(value.to_string()),
+            "License" => info.license = Some(value.to_string()),
+            "Description" => info.description = value.to_string(),
+            "" if last_key == "Description" => {
+                info.description.push(if value.is_empty() { '\n' } else { ' ' });
+                info.description.push_str(value);
+            }
+            _ => {}
+        }
+
+        if !key.is_empty() {
+            last_key = key;
+        }
+    }
+
+    if let (Some(version), Some(release)) = (&mut info.version, release) {
+        version.push('-');
+        version.push_str(release);
+    }
+
+    if info.description.is_empty() {
+        info.description = summary.to_string();
+    }
+
+    info
+}
+
+/// Returns the spec DNF commands take for a package, `name.arch` when the
+/// architecture is known, so the other builds of the package are left alone.
+fn package_spec(pkg: &SearchResult) -> String {
+    match &pkg.arch {
+        Some(arch) => format!("{}.{}", pkg.identifier, arch),
+        None => pkg.identifier.clone(),
+    }
+}
+
+/// Returns `true` if two results are the same package built for the same
+/// architecture, whichever repository they come from.
+fn same_build(a: &SearchResult, b: &SearchResult) -> bool {
+    a.identifier == b.identifier && a.arch == b.arch
+}
+
+/// Splits a DNF package name into name and architecture.
+///
+/// Format: PackageName.arch (e.g., "test.x86_64" or "test.noarch")
+fn split_arch(full_name: &str) -> (&str, Option<&str>) {
+    if let Some(dot_pos) = full_name.rfind('.') {
+        let (name, arch_part) = full_name.split_at(dot_pos);
+        (name, Some(&arch_part[1..])) // Skip the dot
+    } else {
+        (full_name, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_arch_from_the_last_dot() {
+        assert_eq!(split_arch("bash.x86_64"), ("bash", Some("x86_64")));
+        assert_eq!(split_arch("python3.12.x86_64"), ("python3.12", Some("x86_64")));
+        assert_eq!(split_arch("python3-pip.noarch"), ("python3-pip", Some("noarch")));
+        assert_eq!(split_arch("bash"), ("bash", None));
+    }
+
+    #[test]
+    fn parses_wrapped_names_in_columns() {
+        let output = "\
+Last metadata expiration check: 0:12:01 ago on Mon 15 Apr 2024 10:00:00 AM CEST.
+
+NetworkManager-openvpn-gnome.x86_64
+                                 1:1.10.2-4.fc40                    updates
+bash.x86_64                      5.2.26-3.fc40                      updates
+python3.12.x86_64                3.12.3-2.fc40                      updates
+";
+
+        assert_eq!(
+            parse_dnf_columns(output),
+            [
+                ("NetworkManager-openvpn-gnome.x86_64", "1:1.10.2-4.fc40", "updates"),
+                ("bash.x86_64", "5.2.26-3.fc40", "updates"),
+                ("python3.12.x86_64", "3.12.3-2.fc40", "updates"),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_obsoleting_packages() {
+        let output = "\
+bash.x86_64                      5.2.26-3.fc40                      updates
+Obsoleting Packages
+python3-foo.noarch               1.0-1.fc40                         updates
+    python3-oldfoo.noarch        0.9-1.fc39                         @fedora
+";
+
+        let upgrades = parse_dnf_upgrades(output);
+        assert_eq!(upgrades.len(), 1);
+        assert_eq!(upgrades[0].identifier, "bash");
+        assert_eq!(upgrades[0].arch.as_deref(), Some("x86_64"));
+        assert_eq!(upgrades[0].version.as_deref(), Some("5.2.26-3.fc40"));
+        assert_eq!(upgrades[0].origin.as_deref(), Some("updates"));
+    }
+
+    #[test]
+    fn builds_package_specs() {
+        let mut pkg = SearchResult {
+            backend: "dnf".into(),
+            name: "python3.12".into(),
+            identifier: "python3.12".into(),
+            ..Default::default()
+        };
+        assert_eq!(package_spec(&pkg), "python3.12");
+
+        pkg.arch = Some("i686".into());
+        assert_eq!(package_spec(&pkg), "python3.12.i686");
+    }
+}
@@ -3,92 +3,309 @@
 use tokio::process::Command;
 use indicatif::ProgressBar;
 use async_trait::async_trait;
+use crate::config::{FlatpakConfig, FlatpakInstallation};
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
 use crate::models::result::SearchResult;
-use super::Backend;
+use crate::utils::command::{capture, run};
+use super::{mark_installed_by, Backend};
 
 /// Backend implementation for the Flatpak package manager.
-pub struct FlatpakBackend;
+///
+/// Results carry the remote they were found in as `origin` and their branch,
+/// so the same application offered by several remotes shows up once per
+/// remote and is installed from the one the user picked.
+pub struct FlatpakBackend {
+    /// The remote used for packages without an origin, `flathub` by default.
+    remote: String,
+    /// The installation new applications go to.
+    installation: FlatpakInstallation,
+}
+
+impl FlatpakBackend {
+    /// Creates the backend with the settings from the `[flatpak]`
+    /// configuration table.
+    pub fn new(config: &FlatpakConfig) -> Self {
+        Self {
+            remote: config.remote.clone(),
+            installation: config.installation,
+        }
+    }
+
+    /// Returns the remote `pkg` comes from.
+    fn remote_of<'a>(&'a self, pkg: &'a SearchResult) -> &'a str {
+        pkg.origin.as_deref().unwrap_or(&self.remote)
+    }
+}
 
 #[async_trait]
 impl Backend for FlatpakBackend {
-    fn name(&self) -> &'static str { "flatpak" }
+    fn name(&self) -> &str { "flatpak" }
 
     fn exists(&self) -> bool {
         which::which("flatpak").is_ok()
     }
 
-    async fn search(&self, query: &str, pb: ProgressBar) -> Vec<SearchResult> {
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
         pb.set_message("Searching Flatpak...");
 
-        let output = Command::new("flatpak")
+        let mut search = Command::new("flatpak");
+        search
             .arg("search")
-            .arg(query)
-            .output()
-            .await;
-
-        let results = if let Ok(out) = output {
-            parse_flatpak(&String::from_utf8_lossy(&out.stdout))
-        } else {
-            vec![]
-        };
+            .arg("--columns=name,description,application,version,branch,remotes")
+            .arg(query);
+
+        // List installed applications alongside the search to mark matches,
+        // each remote and branch on its own
+        let (output, installed) =
+            tokio::join!(capture(&mut search), self.list_installed(ProgressBar::hidden()));
+
+        let mut results = parse_flatpak(&output?);
+        mark_installed_by(
+            &mut results,
+            &installed.unwrap_or_default(),
+            SearchResult::same_package,
+        );
 
         pb.finish_with_message("Flatpak search done");
-        results
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Flatpak applications...");
+
+        let stdout = capture(
+            Command::new("flatpak")
+                .arg("list")
+                .arg("--app")
+                .arg("--columns=name,description,application,version,branch,origin"),
+        )
+        .await?;
+
+        let results = parse_flatpak(&stdout)
+            .into_iter()
+            .map(|mut r| {
+                r.installed = true;
+                r.installed_version = r.version.clone();
+                r
+            })
+            .collect();
+
+        pb.finish_with_message("Flatpak listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Flatpak updates...");
+
+        let mut remote_ls = Command::new("flatpak");
+        remote_ls
+            .arg("remote-ls")
+            .arg("--updates")
+            .arg("--app")
+            .arg("--columns=name,description,application,version,branch,origin");
+
+        let (output, installed) =
+            tokio::join!(capture(&mut remote_ls), self.list_installed(ProgressBar::hidden()));
+
+        let mut results = parse_flatpak(&output?);
+        mark_installed_by(
+            &mut results,
+            &installed.unwrap_or_default(),
+            SearchResult::same_package,
+        );
+        for result in &mut results {
+            result.installed = true;
+        }
+
+        pb.finish_with_message("Flatpak update check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(
+            Command::new("flatpak")
+                .arg("remote-info")
+                .arg(self.remote_of(pkg))
+                .arg(flatpak_ref(pkg)),
+        )
+        .await?;
+
+        let mut info = parse_flatpak_info(&stdout);
+        if info.name.is_empty() {
+            return Err(BackendError::ParseError(format!(
+                "no application details for {} in flatpak remote-info output",
+                pkg.identifier
+            )));
+        }
+        info.repository = Some(self.remote_of(pkg).to_string());
+
+        // remote-info only has a one-line summary
+        if info.description.is_empty() {
+            info.description = pkg.description.clone();
+        }
+
+        Ok(info)
     }
 
-    async fn install(&self, pkg: &SearchResult) -> Result<(), String> {
-        let status = Command::new("flatpak")
-            .arg("install")
-            .arg("flathub")
-            .arg(&pkg.identifier)
-            .status()
-            .await;
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        // flatpak install takes a single remote, so there is one transaction
+        // per remote
+        let mut remotes: Vec<&str> = pkgs.iter().map(|p| self.remote_of(p)).collect();
+        remotes.sort();
+        remotes.dedup();
 
-        if let Ok(s) = status {
-            if s.success() { return Ok(()); }
+        for remote in remotes {
+            let mut command = Command::new("flatpak");
+            command.arg("install").arg(match self.installation {
+                FlatpakInstallation::User => "--user",
+                FlatpakInstallation::System => "--system",
+            });
+            if assume_yes {
+                command.arg("--assumeyes").arg("--noninteractive");
+            }
+
+            run(command.arg(remote).args(
+                pkgs.iter()
+                    .filter(|p| self.remote_of(p) == remote)
+                    .map(flatpak_ref),
+            ))
+            .await?;
         }
-        Err("Flatpak install failed".into())
+
+        Ok(())
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        // Without --user or --system, flatpak finds the installation itself
+        run(Command::new("flatpak")
+            .arg("uninstall")
+            .args(pkgs.iter().map(flatpak_ref)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(Command::new("flatpak")
+            .arg("update")
+            .args(pkgs.iter().map(flatpak_ref)))
+        .await
+    }
+}
+
+/// Returns the ref of a package, `<application>//<branch>` when the branch
+/// is known, which every flatpak command accepts in place of the full ref.
+fn flatpak_ref(pkg: &SearchResult) -> String {
+    match &pkg.branch {
+        Some(branch) => format!("{}//{}", pkg.identifier, branch),
+        None => pkg.identifier.clone(),
     }
 }
 
 /// Parses Flatpak search output.
 ///
-/// Flatpak search output format: `Name\tDescription\tApplicationID\tVersion\tBranch\tRemote`
+/// Flatpak search output format: `Name\tDescription\tApplicationID\tVersion\tBranch\tRemotes`
 /// Example: `Discord\tChat and voice client\tcom.discordapp.Discord\t1.0.0\tstable\tflathub`
+///
+/// The remotes column lists every remote offering the application, separated
+/// by commas, and each gets its own result. `flatpak list` and `flatpak
+/// remote-ls` are invoked with the same columns, ending in the origin remote,
+/// so their output is parsed here as well.
 fn parse_flatpak(s: &str) -> Vec<SearchResult> {
     s.lines()
         .filter(|line| {
             let line = line.trim();
             !line.is_empty() && line.contains('\t')
         })
-        .filter_map(|line| {
+        .flat_map(|line| {
             let parts: Vec<&str> = line.split('\t').map(|s| s.trim()).collect();
             if parts.len() < 3 {
-                return None;
+                return vec![];
             }
 
             let name = parts[0];
             let description = parts.get(1).copied().unwrap_or("");
             let application_id = parts.get(2).copied().unwrap_or("");
             let version = parts.get(3).copied().filter(|v| !v.is_empty());
+            let branch = parts.get(4).copied().filter(|b| !b.is_empty());
+            let remotes: Vec<Option<&str>> = match parts.get(5) {
+                Some(remotes) if !remotes.is_empty() => {
+                    remotes.split(',').map(|r| Some(r.trim())).collect()
+                }
+                _ => vec![None],
+            };
 
             // Skip if essential fields are missing
             if name.is_empty() || application_id.is_empty() {
-                return None;
+                return vec![];
             }
 
-            Some(SearchResult {
-                backend: "flatpak".into(),
-                name: name.to_string(),
-                identifier: application_id.to_string(),
-                description: if description.is_empty() {
-                    "No description available".to_string()
-                } else {
-                    description.to_string()
-                },
-                version: version.map(|v| v.to_string()),
-            })
+            remotes
+                .into_iter()
+                .map(|remote| SearchResult {
+                    backend: "flatpak".into(),
+                    name: name.to_string(),
+                    identifier: application_id.to_string(),
+                    description: if description.is_empty() {
+                        "No description available".to_string()
+                    } else {
+                        description.to_string()
+                    },
+                    version: version.map(|v| v.to_string()),
+                    origin: remote.map(|r| r.to_string()),
+                    branch: branch.map(|b| b.to_string()),
+                    ..Default::default()
+                })
+                .collect()
         })
         .collect()
 }
 
+/// Parses `flatpak remote-info` output.
+///
+/// The first line is `Name - Summary`, followed by right-aligned
+/// `Key: Value` lines.
+/// Example:
+/// ```text
+/// Discord - Messaging, voice and video client
+///
+///          ID: com.discordapp.Discord
+///     Version: 0.0.50
+///     License: LicenseRef-proprietary
+///    Download: 95.2 MB
+///     Runtime: org.freedesktop.Platform/x86_64/23.08
+/// ```
+fn parse_flatpak_info(s: &str) -> PackageInfo {
+    let mut info = PackageInfo {
+        backend: "flatpak".into(),
+        ..PackageInfo::default()
+    };
+    let mut lines = s.lines().skip_while(|line| line.trim().is_empty());
+
+    if let Some(title) = lines.next() {
+        let (name, summary) = title.split_once(" - ").unwrap_or((title, ""));
+        info.name = name.trim().to_string();
+        info.description = summary.trim().to_string();
+    }
+
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+
+        match key.trim() {
+            "Version" => info.version = Some(value),
+            "License" => info.license = Some(value),
+            "Installed" => info.size = Some(value),
+            "Download" if info.size.is_none() => info.size = Some(value),
+            "Runtime" | "Sdk" => info.dependencies.push(value),
+            _ => {}
+        }
+    }
+
+    info
+}
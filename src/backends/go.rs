@@ -0,0 +1,392 @@
+//! Go backend for installing command-line tools with `go install`.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, run};
+use super::{io_error, Backend};
+
+/// How many parent directories of a package path are tried when looking up
+/// the module providing it, e.g. `golang.org/x/tools` for
+/// `golang.org/x/tools/cmd/goimports`.
+const MAX_MODULE_DEPTH: usize = 3;
+
+/// Backend implementation for binaries installed with `go install`.
+pub struct GoBackend;
+
+#[async_trait]
+impl Backend for GoBackend {
+    fn name(&self) -> &str { "go" }
+
+    fn exists(&self) -> bool {
+        which::which("go").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching Go binaries...");
+
+        // There is no search in the Go toolchain, so installed binaries are
+        // matched by name and a query that looks like a package path is
+        // looked up in the module proxy
+        let installed = installed_binaries().await?;
+        let query_lower = query.to_lowercase();
+
+        let mut results: Vec<SearchResult> = installed
+            .iter()
+            .filter(|b| {
+                b.name.to_lowercase().contains(&query_lower)
+                    || b.package.to_lowercase().contains(&query_lower)
+            })
+            .map(GoBinary::to_result)
+            .collect();
+
+        if query.contains('/') && !results.iter().any(|r| r.identifier == query) {
+            pb.set_message("Looking up Go module...");
+            if let Some(module) = resolve_module(query).await? {
+                results.push(SearchResult {
+                    backend: "go".into(),
+                    name: binary_name(query).to_string(),
+                    identifier: query.to_string(),
+                    description: format!("Go package from module {}", module.path),
+                    version: Some(module.version),
+                    metadata: BTreeMap::from([("module".into(), module.path)]),
+                    ..Default::default()
+                });
+            }
+        }
+
+        pb.finish_with_message("Go search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Go binaries...");
+
+        let results = installed_binaries()
+            .await?
+            .iter()
+            .map(GoBinary::to_result)
+            .collect();
+
+        pb.finish_with_message("Go listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Go upgrades...");
+
+        // Binaries built from a local checkout report "(devel)" and can't be
+        // looked up
+        let installed: Vec<GoBinary> = installed_binaries()
+            .await?
+            .into_iter()
+            .filter(|b| b.version.starts_with('v'))
+            .collect();
+
+        if installed.is_empty() {
+            pb.finish_with_message("Go upgrade check done");
+            return Ok(vec![]);
+        }
+
+        // -u reports the newest version of each module, -e reports lookup
+        // errors in the output instead of failing the whole command
+        let stdout = capture(
+            Command::new("go")
+                .arg("list")
+                .arg("-m")
+                .arg("-u")
+                .arg("-e")
+                .arg("-json")
+                .args(installed.iter().map(|b| format!("{}@{}", b.module, b.version))),
+        )
+        .await?;
+        let modules = parse_go_modules(&stdout)?;
+
+        let results = installed
+            .iter()
+            .filter_map(|binary| {
+                let update = modules
+                    .iter()
+                    .find(|m| m.path == binary.module)?
+                    .update
+                    .as_ref()?;
+
+                let mut result = binary.to_result();
+                result.version = Some(update.version.clone());
+                Some(result)
+            })
+            .collect();
+
+        pb.finish_with_message("Go upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let module = resolve_module(&pkg.identifier).await?.ok_or_else(|| {
+            BackendError::ParseError(format!("no Go module provides {}", pkg.identifier))
+        })?;
+
+        Ok(PackageInfo {
+            backend: "go".into(),
+            name: pkg.name.clone(),
+            version: Some(module.version),
+            description: format!("{} from module {}", pkg.identifier, module.path),
+            homepage: Some(format!("https://pkg.go.dev/{}", pkg.identifier)),
+            repository: Some(module.path),
+            ..PackageInfo::default()
+        })
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], _assume_yes: bool) -> Result<(), BackendError> {
+        // go install only accepts several packages at once when they come
+        // from the same module
+        for pkg in pkgs {
+            run(Command::new("go").arg("install").arg(install_spec(pkg))).await?;
+        }
+        Ok(())
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        // The toolchain has no uninstall, installed binaries are plain files
+        let installed = installed_binaries().await?;
+        let paths: Vec<&PathBuf> = installed
+            .iter()
+            .filter(|b| pkgs.iter().any(|p| p.identifier == b.package && p.name == b.name))
+            .map(|b| &b.path)
+            .collect();
+
+        for path in paths {
+            match tokio::fs::remove_file(path).await {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(io_error(path, e)),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        // Results from list_upgrades carry the update version, so this
+        // installs exactly what was listed
+        self.install(pkgs, false).await
+    }
+}
+
+/// A binary in the Go bin directory, as reported by `go version -m`.
+struct GoBinary {
+    /// Path of the binary.
+    path: PathBuf,
+    /// File name of the binary.
+    name: String,
+    /// The main package it was built from, as passed to `go install`.
+    package: String,
+    /// The module providing the main package.
+    module: String,
+    /// The module version, `(devel)` for local builds.
+    version: String,
+    /// The Go release it was built with.
+    go_version: String,
+}
+
+impl GoBinary {
+    fn to_result(&self) -> SearchResult {
+        SearchResult {
+            backend: "go".into(),
+            name: self.name.clone(),
+            identifier: self.package.clone(),
+            description: format!("{} built with {}", self.package, self.go_version),
+            version: Some(self.version.clone()),
+            installed: true,
+            installed_version: Some(self.version.clone()),
+            metadata: BTreeMap::from([
+                ("module".into(), self.module.clone()),
+                ("go".into(), self.go_version.clone()),
+            ]),
+            ..Default::default()
+        }
+    }
+}
+
+/// Returns the directory `go install` puts binaries in: `$GOBIN`, or the
+/// `bin` directory of the first `$GOPATH` entry.
+async fn bin_dir() -> Result<PathBuf, BackendError> {
+    let stdout = capture(Command::new("go").arg("env").arg("GOBIN").arg("GOPATH")).await?;
+    let mut lines = stdout.lines();
+    let gobin = lines.next().unwrap_or("").trim();
+    let gopath = lines.next().unwrap_or("").trim();
+
+    if !gobin.is_empty() {
+        return Ok(PathBuf::from(gobin));
+    }
+
+    match gopath.split(':').next().filter(|p| !p.is_empty()) {
+        Some(gopath) => Ok(PathBuf::from(gopath).join("bin")),
+        None => Err(BackendError::ParseError("neither GOBIN nor GOPATH is set".into())),
+    }
+}
+
+/// Lists the Go binaries in the bin directory.
+async fn installed_binaries() -> Result<Vec<GoBinary>, BackendError> {
+    let dir = bin_dir().await?;
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    // Given a directory, `go version -m` describes every Go binary in it
+    let stdout = capture(Command::new("go").arg("version").arg("-m").arg(&dir)).await?;
+    Ok(parse_go_version(&stdout))
+}
+
+/// Parses `go version -m` output.
+///
+/// Each binary starts with an unindented `path: go-version` line, followed
+/// by tab-indented build information.
+/// Example:
+/// ```text
+/// /home/user/go/bin/gopls: go1.22.0
+///         path    golang.org/x/tools/gopls
+///         mod     golang.org/x/tools/gopls        v0.15.1 h1:xdg2L3...
+///         dep     golang.org/x/mod        v0.15.0 h1:SernR4...
+///         build   -buildmode=exe
+/// ```
+fn parse_go_version(s: &str) -> Vec<GoBinary> {
+    let mut binaries: Vec<GoBinary> = Vec::new();
+
+    for line in s.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            let Some((path, go_version)) = line.rsplit_once(": ") else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            binaries.push(GoBinary {
+                path,
+                name,
+                package: String::new(),
+                module: String::new(),
+                version: String::new(),
+                go_version: go_version.trim().to_string(),
+            });
+            continue;
+        }
+
+        let Some(binary) = binaries.last_mut() else {
+            continue;
+        };
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["path", package, ..] => binary.package = package.to_string(),
+            ["mod", module, version, ..] => {
+                binary.module = module.to_string();
+                binary.version = version.to_string();
+            }
+            _ => {}
+        }
+    }
+
+    // Binaries without build information can't be reinstalled
+    binaries.retain(|b| !b.package.is_empty() && !b.module.is_empty());
+    binaries
+}
+
+/// A module as reported by `go list -m -json`.
+#[derive(Deserialize)]
+struct GoModule {
+    #[serde(rename = "Path")]
+    path: String,
+    #[serde(rename = "Version", default)]
+    version: String,
+    /// The newest version, with `-u` when it is newer than `version`.
+    #[serde(rename = "Update")]
+    update: Option<Box<GoModule>>,
+    /// Set with `-e` when the module couldn't be looked up.
+    #[serde(rename = "Error")]
+    error: Option<serde_json::Value>,
+}
+
+/// Parses `go list -m -json` output, a stream of JSON objects.
+///
+/// Modules that couldn't be looked up are skipped.
+fn parse_go_modules(s: &str) -> Result<Vec<GoModule>, BackendError> {
+    let modules = serde_json::Deserializer::from_str(s)
+        .into_iter::<GoModule>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| BackendError::ParseError(format!("go list JSON output: {}", e)))?;
+
+    Ok(modules.into_iter().filter(|m| m.error.is_none()).collect())
+}
+
+/// Finds the module providing `package` and its latest version.
+///
+/// Tries the package path itself, then its parent directories, since a
+/// main package often lives in a subdirectory of its module. Returns
+/// `None` if no module was found.
+async fn resolve_module(package: &str) -> Result<Option<GoModule>, BackendError> {
+    let candidates = std::iter::successors(Some(package), |path| {
+        path.rsplit_once('/').map(|(parent, _)| parent)
+    })
+    .filter(|path| path.contains('/'))
+    .take(MAX_MODULE_DEPTH + 1);
+
+    for candidate in candidates {
+        let stdout = capture(
+            Command::new("go")
+                .arg("list")
+                .arg("-m")
+                .arg("-e")
+                .arg("-json")
+                .arg(format!("{}@latest", candidate)),
+        )
+        .await?;
+
+        if let Some(module) = parse_go_modules(&stdout)?.into_iter().next() {
+            return Ok(Some(module));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Returns the `package@version` argument for `go install`.
+///
+/// Uses the version of the result when it is a module version, and
+/// `latest` otherwise, e.g. for local `(devel)` builds.
+fn install_spec(pkg: &SearchResult) -> String {
+    match pkg.version.as_deref().filter(|v| v.starts_with('v')) {
+        Some(version) => format!("{}@{}", pkg.identifier, version),
+        None => format!("{}@latest", pkg.identifier),
+    }
+}
+
+/// Returns the name of the binary `go install` builds for a package, the
+/// last path element that isn't a major version suffix like `v2`.
+fn binary_name(package: &str) -> &str {
+    let mut elements = package.rsplit('/');
+    let last = elements.next().unwrap_or(package);
+
+    let is_major_version = last.len() > 1
+        && last.starts_with('v')
+        && last[1..].chars().all(|c| c.is_ascii_digit());
+
+    match elements.next() {
+        Some(parent) if is_major_version => parent,
+        _ => last,
+    }
+}
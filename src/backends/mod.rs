@@ -1,25 +1,48 @@
 //! Package manager backends for searching and installing packages.
 //!
 //! This module provides a trait-based interface for different package managers
-//! (DNF, Flatpak, Cargo) and automatically detects which ones are available
-//! on the system.
+//! (DNF, Zypper, APT, Pacman, AUR, Nix, Flatpak, Snap, Homebrew, Cargo, Go,
+//! pip, npm, AppImage) and automatically detects which ones are available on
+//! the system. Further backends can be added at runtime as `fi-backend-<name>`
+//! executables that speak the protocol described in `plugin.rs`.
 
 mod dnf;
+mod zypper;
+mod apt;
+mod pacman;
+mod aur;
+mod nix;
 mod flatpak;
+mod snap;
+mod brew;
 mod cargo;
+mod go;
+mod pip;
+mod npm;
+mod appimage;
+mod plugin;
+
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
 
 use async_trait::async_trait;
+use tokio::process::Command;
 use indicatif::ProgressBar;
+use crate::config::Config;
+use crate::error::{BackendError, FiError};
+use crate::models::info::PackageInfo;
 use crate::models::result::SearchResult;
 
 /// Trait for package manager backends.
 ///
-/// Each backend implements this trait to provide search and installation
-/// capabilities for a specific package manager.
+/// Each backend implements this trait to provide search, installation,
+/// removal, listing, upgrade and package detail capabilities for a specific
+/// package manager.
 #[async_trait]
 pub trait Backend: Send + Sync {
-    /// Returns the name of the backend (e.g., "dnf", "flatpak", "cargo").
-    fn name(&self) -> &'static str;
+    /// Returns the name of the backend (e.g., "dnf", "apt", "flatpak", "cargo").
+    fn name(&self) -> &str;
 
     /// Checks if the backend's package manager is available on the system.
     fn exists(&self) -> bool;
@@ -33,36 +56,221 @@ pub trait Backend: Send + Sync {
     ///
     /// # Returns
     ///
-    /// A vector of search results matching the query.
-    async fn search(&self, query: &str, pb: ProgressBar) -> Vec<SearchResult>;
+    /// A vector of search results matching the query, or an error if the
+    /// package manager failed. No matches is not an error.
+    async fn search(&self, query: &str, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError>;
+
+    /// Lists the packages installed through this backend.
+    ///
+    /// # Arguments
+    ///
+    /// * `pb` - Progress bar for displaying listing progress
+    ///
+    /// # Returns
+    ///
+    /// A vector of installed packages, with `version` set to the installed
+    /// version when the package manager reports it.
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError>;
+
+    /// Lists installed packages that have a newer version available.
+    ///
+    /// # Arguments
+    ///
+    /// * `pb` - Progress bar for displaying progress
+    ///
+    /// # Returns
+    ///
+    /// A vector of upgradable packages, with `version` set to the new version
+    /// and `installed_version` to the current one when known.
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError>;
+
+    /// Fetches detailed information about a package.
+    ///
+    /// # Arguments
+    ///
+    /// * `pkg` - The package to describe, as returned by `search`
+    ///
+    /// # Returns
+    ///
+    /// Returns the package details, or an error on failure.
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError>;
+
+    /// Installs packages in a single transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `pkgs` - The packages to install
+    /// * `assume_yes` - Answer yes to all prompts, and fail rather than
+    ///   prompt where that isn't possible (e.g. a sudo password)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success, or an error on failure.
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError>;
+
+    /// Removes installed packages in a single transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `pkgs` - The packages to remove
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` on success, or an error on failure.
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError>;
 
-    /// Installs a package.
+    /// Upgrades installed packages in a single transaction.
     ///
     /// # Arguments
     ///
-    /// * `pkg` - The package to install
+    /// * `pkgs` - The packages to upgrade, as returned by `list_upgrades`
     ///
     /// # Returns
     ///
-    /// Returns `Ok(())` on success, or an error message on failure.
-    async fn install(&self, pkg: &SearchResult) -> Result<(), String>;
+    /// Returns `Ok(())` on success, or an error on failure.
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError>;
+}
+
+/// Builds a command that runs `program` as root, going through `sudo` unless
+/// fi already runs as root (e.g. inside a container).
+///
+/// With `non_interactive` set, sudo fails instead of prompting for a password.
+fn root_command(program: &str, non_interactive: bool) -> Command {
+    let is_root = std::fs::metadata("/proc/self")
+        .map(|m| m.uid() == 0)
+        .unwrap_or(false);
+
+    if is_root {
+        return Command::new(program);
+    }
+
+    let mut command = Command::new("sudo");
+    if non_interactive {
+        command.arg("--non-interactive");
+    }
+    command.arg(program);
+    command
+}
+
+/// Marks the search results that also appear in `installed`.
+///
+/// Results are matched on the string returned by `key`, and matching results
+/// get `installed` set along with the installed version and the metadata of
+/// the installed package.
+fn mark_installed(
+    results: &mut [SearchResult],
+    installed: &[SearchResult],
+    key: fn(&SearchResult) -> &str,
+) {
+    mark_installed_by(results, installed, |result, i| key(result) == key(i));
+}
+
+/// Like [`mark_installed`], but matches results with `same`, for backends
+/// where one string doesn't identify what is installed.
+fn mark_installed_by(
+    results: &mut [SearchResult],
+    installed: &[SearchResult],
+    same: impl Fn(&SearchResult, &SearchResult) -> bool,
+) {
+    for result in results.iter_mut() {
+        if let Some(found) = installed.iter().find(|i| same(result, i)) {
+            result.installed = true;
+            result.installed_version = found.installed_version.clone();
+            result.metadata.extend(found.metadata.clone());
+        }
+    }
+}
+
+/// Maps a file system error to a backend error naming the path.
+fn io_error(path: &Path, err: io::Error) -> BackendError {
+    let message = format!("{}: {}", path.display(), err);
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => BackendError::PermissionDenied(message),
+        _ => BackendError::Io(message),
+    }
+}
+
+/// Collects the results of per-package lookups, such as the latest version
+/// of each installed package.
+///
+/// A single failed lookup shouldn't hide the others, so failures become
+/// `None`. If every lookup failed (e.g. no network) that is worth reporting,
+/// and the first error is returned instead.
+fn collect_lookups<T>(
+    lookups: Vec<Result<T, BackendError>>,
+) -> Result<Vec<Option<T>>, BackendError> {
+    if let Some(Err(e)) = lookups.first()
+        && lookups.iter().all(|l| l.is_err())
+    {
+        return Err(e.clone());
+    }
+
+    Ok(lookups.into_iter().map(Result::ok).collect())
 }
 
+/// The available backends, and warnings about the configuration that
+/// selected them.
+pub type AvailableBackends = (Vec<Box<dyn Backend>>, Vec<String>);
+
 /// Returns a list of all available backends on the system.
 ///
 /// This function checks which package managers are installed and returns
-/// only the backends that are available.
-pub fn get_available_backends() -> Vec<Box<dyn Backend>> {
-    let backends: Vec<Box<dyn Backend>> = vec![
+/// only the backends that are available, followed by any external backends
+/// found on `PATH`. Backends disabled in the configuration are left out, and
+/// the ones it orders come first.
+///
+/// # Arguments
+///
+/// * `config` - The user configuration
+///
+/// # Returns
+///
+/// The available backends along with warnings for the caller to show, or an
+/// error if the configuration names a backend that can't exist. Names that
+/// may be external backends missing from this machine only cause a warning.
+pub fn get_available_backends(config: &Config) -> Result<AvailableBackends, FiError> {
+    let mut backends: Vec<Box<dyn Backend>> = vec![
         Box::new(dnf::DnfBackend),
-        Box::new(flatpak::FlatpakBackend),
+        Box::new(zypper::ZypperBackend),
+        Box::new(apt::AptBackend),
+        Box::new(pacman::PacmanBackend),
+        Box::new(aur::AurBackend),
+        Box::new(nix::NixBackend),
+        Box::new(flatpak::FlatpakBackend::new(&config.flatpak)),
+        Box::new(snap::SnapBackend),
+        Box::new(brew::BrewBackend),
         Box::new(cargo::CargoBackend),
+        Box::new(go::GoBackend),
+        Box::new(pip::PipBackend::new(&config.pip)),
+        Box::new(npm::NpmBackend),
+        Box::new(appimage::AppImageBackend::new(&config.appimage)),
     ];
 
-    backends
+    // An external backend can't take over the name of a built-in one
+    for plugin in plugin::discover() {
+        if !backends.iter().any(|b| b.name() == plugin.name()) {
+            backends.push(Box::new(plugin));
+        }
+    }
+
+    let names: Vec<&str> = backends.iter().map(|b| b.name()).collect();
+    let warnings = config
+        .check_backend_names(&names)?
         .into_iter()
-        .filter(|b| b.exists())
-        .collect()
-}
+        .map(|name| {
+            format!("unknown backend {} in [backends], no fi-backend-{} on PATH", name, name)
+        })
+        .collect();
 
+    let order = &config.backends.order;
+    backends.retain(|b| !config.backends.disabled.iter().any(|name| name == b.name()));
+    backends.sort_by_key(|b| {
+        order
+            .iter()
+            .position(|name| name == b.name())
+            .unwrap_or(order.len())
+    });
 
+    let backends = backends.into_iter().filter(|b| b.exists()).collect();
+    Ok((backends, warnings))
+}
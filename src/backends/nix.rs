@@ -0,0 +1,423 @@
+//! Nix backend for installing nixpkgs packages into the user's Nix profile.
+
+use std::collections::HashMap;
+
+use futures::future::join_all;
+use serde::Deserialize;
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, run};
+use crate::utils::version::is_newer;
+use super::{collect_lookups, mark_installed, Backend};
+
+/// The flake packages are searched in and installed from.
+const FLAKE: &str = "nixpkgs";
+
+/// Backend implementation for Nix profiles.
+///
+/// Packages are identified by their attribute path in nixpkgs (e.g.
+/// `ripgrep` or `python312Packages.black`) and installed as
+/// `nixpkgs#<attribute path>`.
+pub struct NixBackend;
+
+#[async_trait]
+impl Backend for NixBackend {
+    fn name(&self) -> &str { "nix" }
+
+    fn exists(&self) -> bool {
+        which::which("nix").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching nixpkgs...");
+
+        let mut search = nix();
+        search.arg("search").arg(FLAKE).arg(query).arg("--json");
+
+        // List the profile alongside the search to mark matches
+        let (output, installed) =
+            tokio::join!(capture(&mut search), self.list_installed(ProgressBar::hidden()));
+
+        let mut results = match output {
+            Ok(stdout) => parse_nix_search(&stdout)?,
+            // Older versions exit with an error when nothing matches
+            Err(BackendError::CommandFailed { stderr, .. })
+                if stderr.contains("no results") => vec![],
+            Err(e) => return Err(e),
+        };
+        mark_installed(&mut results, &installed.unwrap_or_default(), |r| &r.identifier);
+
+        pb.finish_with_message("nixpkgs search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Nix profile...");
+
+        let stdout = capture(nix().arg("profile").arg("list").arg("--json")).await?;
+        let results = parse_nix_profile(&stdout)?
+            .into_iter()
+            .map(|element| element.to_result())
+            .collect();
+
+        pb.finish_with_message("Nix profile listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Nix profile upgrades...");
+
+        // Nix can't list pending upgrades, so the version of each package
+        // installed from nixpkgs is compared with the current nixpkgs
+        let stdout = capture(nix().arg("profile").arg("list").arg("--json")).await?;
+        let installed: Vec<ProfileElement> = parse_nix_profile(&stdout)?
+            .into_iter()
+            .filter(|element| element.from_nixpkgs && element.version.is_some())
+            .collect();
+
+        let latest = join_all(installed.iter().map(|e| attribute_version(&e.attr_path))).await;
+
+        let latest = collect_lookups(latest)?;
+
+        let results = installed
+            .iter()
+            .zip(latest)
+            .filter_map(|(element, latest)| {
+                let latest = latest?;
+                if !is_newer(&latest, element.version.as_deref()?) {
+                    return None;
+                }
+                let mut result = element.to_result();
+                result.version = Some(latest);
+                Some(result)
+            })
+            .collect();
+
+        pb.finish_with_message("Nix profile upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let mut meta = nix();
+        meta.arg("eval")
+            .arg("--json")
+            .arg(format!("{}#{}.meta", FLAKE, pkg.identifier));
+
+        let (meta, version) =
+            tokio::join!(capture(&mut meta), attribute_version(&pkg.identifier));
+
+        let mut info = parse_nix_meta(&meta?)?;
+        info.name = pkg.identifier.clone();
+        info.version = version.ok();
+        info.repository = Some(FLAKE.into());
+
+        Ok(info)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], _assume_yes: bool) -> Result<(), BackendError> {
+        // Installing into the user's profile never prompts or needs root
+        run(nix()
+            .arg("profile")
+            .arg("install")
+            .args(pkgs.iter().map(|p| format!("{}#{}", FLAKE, p.identifier))))
+        .await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(nix()
+            .arg("profile")
+            .arg("remove")
+            .args(element_names(pkgs).await?))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(nix()
+            .arg("profile")
+            .arg("upgrade")
+            .args(element_names(pkgs).await?))
+        .await
+    }
+}
+
+/// Builds a nix command with the `nix-command` and `flakes` features
+/// enabled, which many installations still consider experimental.
+fn nix() -> Command {
+    let mut command = Command::new("nix");
+    command
+        .arg("--extra-experimental-features")
+        .arg("nix-command flakes");
+    command
+}
+
+/// Looks up the profile element names of installed packages, which is what
+/// `nix profile remove` and `upgrade` take.
+async fn element_names(pkgs: &[SearchResult]) -> Result<Vec<String>, BackendError> {
+    let stdout = capture(nix().arg("profile").arg("list").arg("--json")).await?;
+
+    Ok(parse_nix_profile(&stdout)?
+        .into_iter()
+        .filter(|element| pkgs.iter().any(|p| p.identifier == element.attr_path))
+        .map(|element| element.name)
+        .collect())
+}
+
+/// Evaluates the version of a nixpkgs attribute.
+async fn attribute_version(attr_path: &str) -> Result<String, BackendError> {
+    let stdout = capture(
+        nix()
+            .arg("eval")
+            .arg("--raw")
+            .arg(format!("{}#{}.version", FLAKE, attr_path)),
+    )
+    .await?;
+
+    Ok(stdout.trim().to_string())
+}
+
+/// Strips the `legacyPackages.<system>.` or `packages.<system>.` prefix of a
+/// flake output attribute, leaving the nixpkgs attribute path.
+fn strip_output_prefix(attr: &str) -> &str {
+    for prefix in ["legacyPackages.", "packages."] {
+        if let Some(rest) = attr.strip_prefix(prefix) {
+            return rest.split_once('.').map_or(rest, |(_system, path)| path);
+        }
+    }
+    attr
+}
+
+/// An entry of `nix search --json` output.
+#[derive(Deserialize)]
+struct NixSearchEntry {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Parses `nix search --json` output.
+///
+/// The output maps flake output attributes to package details.
+/// Example:
+/// ```text
+/// {"legacyPackages.x86_64-linux.ripgrep":{"pname":"ripgrep","version":"14.1.0",
+///  "description":"Utility that combines the usability of The Silver Searcher with grep"}}
+/// ```
+fn parse_nix_search(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let entries: HashMap<String, NixSearchEntry> = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("nix search JSON output: {}", e)))?;
+
+    let mut results: Vec<SearchResult> = entries
+        .into_iter()
+        .map(|(attr, entry)| {
+            let attr_path = strip_output_prefix(&attr).to_string();
+            SearchResult {
+                backend: "nix".into(),
+                name: attr_path.clone(),
+                identifier: attr_path,
+                description: if entry.description.is_empty() {
+                    "No description available".to_string()
+                } else {
+                    entry.description
+                },
+                version: Some(entry.version).filter(|v| !v.is_empty()),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.identifier.cmp(&b.identifier));
+    Ok(results)
+}
+
+/// The document printed by `nix profile list --json`.
+#[derive(Deserialize)]
+struct NixProfile {
+    elements: NixProfileElements,
+}
+
+/// Profile elements are keyed by name since profile version 3, and a list
+/// before that.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NixProfileElements {
+    Named(HashMap<String, NixProfileElement>),
+    Listed(Vec<NixProfileElement>),
+}
+
+#[derive(Deserialize)]
+struct NixProfileElement {
+    #[serde(rename = "attrPath")]
+    attr_path: Option<String>,
+    #[serde(rename = "originalUrl")]
+    original_url: Option<String>,
+    #[serde(rename = "storePaths", default)]
+    store_paths: Vec<String>,
+}
+
+/// A package installed in the profile.
+struct ProfileElement {
+    /// Name used to remove or upgrade the element.
+    name: String,
+    /// The nixpkgs attribute path, or the name for elements not from a flake.
+    attr_path: String,
+    /// Whether the element was installed from the nixpkgs flake.
+    from_nixpkgs: bool,
+    /// Version taken from the store path.
+    version: Option<String>,
+}
+
+impl ProfileElement {
+    fn to_result(&self) -> SearchResult {
+        SearchResult {
+            backend: "nix".into(),
+            name: self.name.clone(),
+            identifier: self.attr_path.clone(),
+            description: if self.from_nixpkgs {
+                format!("Installed from {}", FLAKE)
+            } else {
+                "Installed into the Nix profile".to_string()
+            },
+            version: self.version.clone(),
+            installed: true,
+            installed_version: self.version.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parses `nix profile list --json` output.
+///
+/// Example:
+/// ```text
+/// {"version":3,"elements":{"ripgrep":{"active":true,"attrPath":
+///  "legacyPackages.x86_64-linux.ripgrep","originalUrl":"flake:nixpkgs",
+///  "storePaths":["/nix/store/1v3k...-ripgrep-14.1.0"]}}}
+/// ```
+fn parse_nix_profile(s: &str) -> Result<Vec<ProfileElement>, BackendError> {
+    let profile: NixProfile = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("nix profile JSON output: {}", e)))?;
+
+    let elements: Vec<(Option<String>, NixProfileElement)> = match profile.elements {
+        NixProfileElements::Named(map) => {
+            let mut elements: Vec<_> = map.into_iter().map(|(k, v)| (Some(k), v)).collect();
+            elements.sort_by(|a, b| a.0.cmp(&b.0));
+            elements
+        }
+        NixProfileElements::Listed(list) => list.into_iter().map(|v| (None, v)).collect(),
+    };
+
+    Ok(elements
+        .into_iter()
+        .filter_map(|(name, element)| {
+            let (store_name, version) = element
+                .store_paths
+                .first()
+                .map(|path| split_store_path(path))
+                .unwrap_or_default();
+
+            let attr_path = element.attr_path.as_deref().map(strip_output_prefix);
+            let name = name
+                .or_else(|| attr_path.map(|a| a.to_string()))
+                .or_else(|| store_name.map(|n| n.to_string()))?;
+
+            Some(ProfileElement {
+                attr_path: attr_path.map_or_else(|| name.clone(), |a| a.to_string()),
+                name,
+                from_nixpkgs: element
+                    .original_url
+                    .is_some_and(|url| url == "flake:nixpkgs"),
+                version: version.map(|v| v.to_string()),
+            })
+        })
+        .collect())
+}
+
+/// Splits a store path like `/nix/store/<hash>-ripgrep-14.1.0` into the
+/// package name and version. The version starts at the first `-` followed
+/// by a digit.
+fn split_store_path(path: &str) -> (Option<&str>, Option<&str>) {
+    let base = path.rsplit('/').next().unwrap_or(path);
+    let Some((_hash, name)) = base.split_once('-') else {
+        return (None, None);
+    };
+
+    let version_start = name
+        .match_indices('-')
+        .find(|(i, _)| name[i + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        .map(|(i, _)| i);
+
+    match version_start {
+        Some(i) => (Some(&name[..i]), Some(&name[i + 1..])),
+        None => (Some(name), None),
+    }
+}
+
+/// The `meta` attribute of a nixpkgs package.
+#[derive(Deserialize)]
+struct NixMeta {
+    #[serde(default)]
+    description: String,
+    #[serde(rename = "longDescription")]
+    long_description: Option<String>,
+    homepage: Option<serde_json::Value>,
+    license: Option<serde_json::Value>,
+}
+
+/// Parses the output of `nix eval --json nixpkgs#<attr>.meta`.
+///
+/// `homepage` may be a list of URLs, and `license` a license object or a
+/// list of them.
+fn parse_nix_meta(s: &str) -> Result<PackageInfo, BackendError> {
+    let meta: NixMeta = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("nix eval JSON output: {}", e)))?;
+
+    fn license_name(license: &serde_json::Value) -> Option<String> {
+        match license {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Object(map) => map
+                .get("spdxId")
+                .or_else(|| map.get("fullName"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            _ => None,
+        }
+    }
+
+    let license = match &meta.license {
+        Some(serde_json::Value::Array(list)) => {
+            let names: Vec<String> = list.iter().filter_map(license_name).collect();
+            Some(names.join(", ")).filter(|n| !n.is_empty())
+        }
+        Some(license) => license_name(license),
+        None => None,
+    };
+
+    let homepage = match meta.homepage {
+        Some(serde_json::Value::String(url)) => Some(url),
+        Some(serde_json::Value::Array(urls)) => {
+            urls.first().and_then(|u| u.as_str()).map(|u| u.to_string())
+        }
+        _ => None,
+    };
+
+    Ok(PackageInfo {
+        backend: "nix".into(),
+        description: meta
+            .long_description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .unwrap_or(meta.description),
+        license,
+        homepage,
+        ..PackageInfo::default()
+    })
+}
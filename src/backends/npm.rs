@@ -0,0 +1,304 @@
+//! npm backend for searching and installing global Node.js packages.
+
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+
+use serde::Deserialize;
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, capture_allowing, run};
+use super::{mark_installed, root_command, Backend};
+
+/// Backend implementation for globally installed npm packages.
+pub struct NpmBackend;
+
+#[async_trait]
+impl Backend for NpmBackend {
+    fn name(&self) -> &str { "npm" }
+
+    fn exists(&self) -> bool {
+        which::which("npm").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching npm...");
+
+        let mut search = Command::new("npm");
+        search.arg("search").arg("--json").arg(query);
+
+        // List global packages alongside the search to mark matches
+        let (output, installed) =
+            tokio::join!(capture(&mut search), self.list_installed(ProgressBar::hidden()));
+
+        let mut results = parse_npm_search(&output?)?;
+        mark_installed(&mut results, &installed.unwrap_or_default(), |r| &r.identifier);
+
+        pb.finish_with_message("npm search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing global npm packages...");
+
+        // npm ls exits with 1 on problems such as extraneous packages, but
+        // still prints the tree
+        let stdout = capture_allowing(
+            Command::new("npm")
+                .arg("ls")
+                .arg("--global")
+                .arg("--depth=0")
+                .arg("--json"),
+            &[1],
+        )
+        .await?;
+        let results = parse_npm_ls(&stdout)?;
+
+        pb.finish_with_message("npm listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking npm upgrades...");
+
+        // npm outdated exits with 1 when anything is outdated
+        let stdout = capture_allowing(
+            Command::new("npm").arg("outdated").arg("--global").arg("--json"),
+            &[1],
+        )
+        .await?;
+        let results = parse_npm_outdated(&stdout)?;
+
+        pb.finish_with_message("npm upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(
+            Command::new("npm")
+                .arg("view")
+                .arg("--json")
+                .arg(&pkg.identifier),
+        )
+        .await?;
+
+        parse_npm_view(&stdout)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        run(global_command(assume_yes)
+            .await?
+            .arg("install")
+            .arg("--global")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(global_command(false)
+            .await?
+            .arg("uninstall")
+            .arg("--global")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        // `npm update -g` stays within the installed major version
+        run(global_command(false)
+            .await?
+            .arg("install")
+            .arg("--global")
+            .args(pkgs.iter().map(|p| format!("{}@latest", p.identifier))))
+        .await
+    }
+}
+
+/// Builds an npm command for changing global packages.
+///
+/// Global packages live under npm's prefix (`npm config get prefix`, set
+/// with `NPM_CONFIG_PREFIX` or `prefix=` in `.npmrc`). The command only goes
+/// through sudo when that prefix isn't owned by the current user, so a prefix
+/// in the home directory never needs root.
+async fn global_command(non_interactive: bool) -> Result<Command, BackendError> {
+    let prefix = capture(Command::new("npm").arg("config").arg("get").arg("prefix")).await?;
+
+    let uid = |path: &str| std::fs::metadata(path).map(|m| m.uid()).ok();
+    let owned = uid(prefix.trim()).is_some_and(|owner| Some(owner) == uid("/proc/self"));
+
+    Ok(if owned {
+        Command::new("npm")
+    } else {
+        root_command("npm", non_interactive)
+    })
+}
+
+/// An entry of `npm search --json` output.
+#[derive(Deserialize)]
+struct NpmSearchEntry {
+    name: String,
+    version: Option<String>,
+    description: Option<String>,
+}
+
+/// Parses `npm search --json` output.
+///
+/// Example: `[{"name":"typescript","version":"5.4.2","description":"TypeScript"}]`
+fn parse_npm_search(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let entries: Vec<NpmSearchEntry> = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("npm search JSON output: {}", e)))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| SearchResult {
+            backend: "npm".into(),
+            name: entry.name.clone(),
+            identifier: entry.name,
+            description: entry
+                .description
+                .filter(|d| !d.is_empty())
+                .unwrap_or_else(|| "No description available".to_string()),
+            version: entry.version,
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// The tree printed by `npm ls --json`.
+#[derive(Deserialize)]
+struct NpmTree {
+    #[serde(default)]
+    dependencies: HashMap<String, NpmTreeEntry>,
+}
+
+#[derive(Deserialize)]
+struct NpmTreeEntry {
+    version: Option<String>,
+}
+
+/// Parses `npm ls --global --depth=0 --json` output.
+///
+/// Example: `{"dependencies":{"typescript":{"version":"5.4.2"}}}`
+fn parse_npm_ls(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let tree: NpmTree = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("npm ls JSON output: {}", e)))?;
+
+    let mut results: Vec<SearchResult> = tree
+        .dependencies
+        .into_iter()
+        .map(|(name, entry)| SearchResult {
+            backend: "npm".into(),
+            name: name.clone(),
+            identifier: name,
+            description: "Global npm package".into(),
+            version: entry.version.clone(),
+            installed: true,
+            installed_version: entry.version,
+            ..Default::default()
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(results)
+}
+
+/// An entry of `npm outdated --json` output.
+#[derive(Deserialize)]
+struct NpmOutdatedEntry {
+    current: Option<String>,
+    latest: String,
+}
+
+/// Parses `npm outdated --global --json` output.
+///
+/// Example: `{"typescript":{"current":"5.3.3","wanted":"5.4.2","latest":"5.4.2"}}`
+fn parse_npm_outdated(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    // Nothing outdated prints nothing at all with some npm versions
+    if s.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let outdated: HashMap<String, NpmOutdatedEntry> = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("npm outdated JSON output: {}", e)))?;
+
+    let mut results: Vec<SearchResult> = outdated
+        .into_iter()
+        .map(|(name, entry)| SearchResult {
+            backend: "npm".into(),
+            name: name.clone(),
+            identifier: name,
+            description: "Update from the npm registry".into(),
+            version: Some(entry.latest),
+            installed: true,
+            installed_version: entry.current,
+            ..Default::default()
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(results)
+}
+
+/// The document printed by `npm view --json`.
+#[derive(Deserialize)]
+struct NpmView {
+    name: String,
+    version: Option<String>,
+    description: Option<String>,
+    license: Option<serde_json::Value>,
+    homepage: Option<String>,
+    repository: Option<serde_json::Value>,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    dist: Option<NpmDist>,
+}
+
+#[derive(Deserialize)]
+struct NpmDist {
+    #[serde(rename = "unpackedSize")]
+    unpacked_size: Option<u64>,
+}
+
+/// Parses `npm view --json` output.
+///
+/// `license` and `repository` are either strings or objects, depending on
+/// how the package's `package.json` declares them.
+fn parse_npm_view(s: &str) -> Result<PackageInfo, BackendError> {
+    let view: NpmView = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("npm view JSON output: {}", e)))?;
+
+    let text = |value: Option<serde_json::Value>, key: &str| match value? {
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Object(map) => map.get(key)?.as_str().map(|s| s.to_string()),
+        _ => None,
+    };
+
+    let mut dependencies: Vec<String> = view
+        .dependencies
+        .into_iter()
+        .map(|(name, range)| format!("{}@{}", name, range))
+        .collect();
+    dependencies.sort();
+
+    Ok(PackageInfo {
+        backend: "npm".into(),
+        name: view.name,
+        version: view.version,
+        description: view.description.unwrap_or_default(),
+        license: text(view.license, "type"),
+        homepage: view.homepage,
+        repository: text(view.repository, "url"),
+        size: view
+            .dist
+            .and_then(|dist| dist.unpacked_size)
+            .map(|bytes| format!("{:.1} KiB", bytes as f64 / 1024.0)),
+        dependencies,
+    })
+}
@@ -0,0 +1,322 @@
+//! Pacman backend for searching and installing Arch Linux packages.
+
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, capture_allowing, run};
+use super::{root_command, Backend};
+
+/// Backend implementation for the Pacman package manager.
+pub struct PacmanBackend;
+
+#[async_trait]
+impl Backend for PacmanBackend {
+    fn name(&self) -> &str { "pacman" }
+
+    fn exists(&self) -> bool {
+        which::which("pacman").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching Pacman...");
+
+        // pacman exits with 1 when nothing matches
+        let stdout = capture_allowing(Command::new("pacman").arg("-Ss").arg(query), &[1]).await?;
+        let results = parse_pacman_search(&stdout, "pacman");
+
+        pb.finish_with_message("Pacman search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Pacman packages...");
+
+        // Only packages from the sync repositories, AUR packages are foreign
+        let stdout = capture(Command::new("pacman").arg("-Qn")).await?;
+        let results = parse_pacman_installed(&stdout, "pacman");
+
+        pb.finish_with_message("Pacman listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Pacman upgrades...");
+
+        // Compares against the local copy of the sync databases, refreshing
+        // them needs root. Exits with 1 when nothing is upgradable.
+        let stdout = capture_allowing(Command::new("pacman").arg("-Qun"), &[1]).await?;
+        let results = parse_pacman_upgrades(&stdout, "pacman");
+
+        pb.finish_with_message("Pacman upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(Command::new("pacman").arg("-Si").arg(&pkg.identifier)).await?;
+
+        let info = parse_pacman_info(&stdout, "pacman");
+        if info.name.is_empty() {
+            return Err(BackendError::ParseError(format!(
+                "no package details for {} in pacman -Si output",
+                pkg.identifier
+            )));
+        }
+
+        Ok(info)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        run(root_command("pacman", assume_yes)
+            .arg("-S")
+            .arg("--noconfirm")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("pacman", false)
+            .arg("-R")
+            .arg("--noconfirm")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        let pending = self.list_upgrades(ProgressBar::hidden()).await?;
+        check_full_upgrade(pkgs, &pending)?;
+
+        // Arch only supports upgrading the whole system. Without -y this
+        // upgrades to the local sync databases list_upgrades compared against.
+        run(root_command("pacman", false).arg("-Su").arg("--noconfirm")).await
+    }
+}
+
+/// Checks that `pkgs` includes every upgrade in `pending`, shared with AUR
+/// helpers.
+///
+/// Upgrading only some packages can leave the others linked against
+/// libraries that are gone, so Arch upgrades are all or nothing.
+pub(super) fn check_full_upgrade(
+    pkgs: &[SearchResult],
+    pending: &[SearchResult],
+) -> Result<(), BackendError> {
+    let missing: Vec<&str> = pending
+        .iter()
+        .filter(|p| !pkgs.iter().any(|pkg| pkg.identifier == p.identifier))
+        .map(|p| p.identifier.as_str())
+        .collect();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(BackendError::PartialUpgrade(missing.join(", ")))
+    }
+}
+
+/// Parses pacman search output, shared with AUR helpers.
+///
+/// Each package takes two lines: `repo/name version [groups] [installed]`,
+/// followed by the description on an indented line. Installed packages are
+/// tagged `[installed]`, or `[installed: version]` when the installed version
+/// differs. AUR helpers add votes and popularity and may tag `(Installed)`.
+/// Example:
+/// ```text
+/// extra/ripgrep 14.1.1-1 [installed]
+///     A search tool that combines the usability of ag with the raw speed of grep
+/// aur/ripgrep-git 14.1.0.r8-1 (+3 0.00)
+///     A search tool (git version)
+/// ```
+pub(super) fn parse_pacman_search(s: &str, backend: &str) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = Vec::new();
+
+    for line in s.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        // Indented lines hold the description of the previous package
+        if line.starts_with(char::is_whitespace) {
+            if let Some(last) = results.last_mut() {
+                if !last.description.is_empty() {
+                    last.description.push(' ');
+                }
+                last.description.push_str(line.trim());
+            }
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let Some((repo, name)) = fields.next().and_then(|f| f.split_once('/')) else {
+            continue;
+        };
+        let version = fields.next().map(|v| v.to_string());
+        let rest = fields.collect::<Vec<_>>().join(" ");
+
+        let installed_version = installed_tag(&rest).map(|tag| match tag {
+            Some(installed) => Some(installed.to_string()),
+            None => version.clone(),
+        });
+
+        results.push(SearchResult {
+            backend: backend.into(),
+            name: name.to_string(),
+            identifier: name.to_string(),
+            description: String::new(),
+            version,
+            installed: installed_version.is_some(),
+            installed_version: installed_version.flatten(),
+            origin: Some(repo.to_string()),
+            ..Default::default()
+        });
+    }
+
+    results
+}
+
+/// Finds the installed tag in the trailing fields of a search result line.
+///
+/// Returns `None` if the package isn't installed, `Some(None)` if the
+/// installed version is the listed one, and `Some(Some(version))` otherwise.
+fn installed_tag(rest: &str) -> Option<Option<&str>> {
+    let lower = rest.to_ascii_lowercase();
+    let start = lower.find("installed")?;
+    let tag = &rest[start + "installed".len()..];
+
+    Some(
+        tag.strip_prefix(':')
+            .and_then(|v| v.split([']', ')']).next())
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty()),
+    )
+}
+
+/// Parses `pacman -Q` output.
+///
+/// Output format: `name version`
+/// Example: `ripgrep 14.1.1-1`
+pub(super) fn parse_pacman_installed(s: &str, backend: &str) -> Vec<SearchResult> {
+    s.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            let version = fields.next()?;
+
+            Some(SearchResult {
+                backend: backend.into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: String::new(),
+                version: Some(version.to_string()),
+                installed: true,
+                installed_version: Some(version.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `pacman -Qu` output, shared with AUR helpers.
+///
+/// Output format: `name old-version -> new-version`, optionally followed by
+/// `[ignored]` for packages pinned in `pacman.conf`, which are skipped.
+/// Example: `ripgrep 14.1.0-1 -> 14.1.1-1`
+pub(super) fn parse_pacman_upgrades(s: &str, backend: &str) -> Vec<SearchResult> {
+    s.lines()
+        .filter(|line| !line.contains("[ignored]"))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, old, "->", new, ..] = fields.as_slice() else {
+                return None;
+            };
+
+            Some(SearchResult {
+                backend: backend.into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: String::new(),
+                version: Some(new.to_string()),
+                installed: true,
+                installed_version: Some(old.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `pacman -Si` output, shared with AUR helpers.
+///
+/// Output format: `Key : Value` lines with the keys padded to a common width.
+/// List values are separated by two spaces and `None` marks an empty list.
+/// Values too long for one line continue on lines indented past the colon.
+/// Only the first package is parsed.
+/// Example:
+/// ```text
+/// Repository      : extra
+/// Name            : ripgrep
+/// Version         : 14.1.1-1
+/// Description     : A search tool that combines the usability of ag with grep
+/// URL             : https://github.com/BurntSushi/ripgrep
+/// Licenses        : MIT  Unlicense
+/// Depends On      : gcc-libs  glibc  pcre2
+/// Installed Size  : 4.66 MiB
+/// ```
+pub(super) fn parse_pacman_info(s: &str, backend: &str) -> PackageInfo {
+    let mut info = PackageInfo {
+        backend: backend.into(),
+        ..PackageInfo::default()
+    };
+    let mut last_key = String::new();
+
+    for line in s.lines() {
+        if line.trim().is_empty() {
+            if !info.name.is_empty() {
+                break;
+            }
+            continue;
+        }
+
+        let (key, value) = match line.split_once(" : ") {
+            Some((key, value)) if !key.starts_with(char::is_whitespace) => {
+                last_key = key.trim().to_string();
+                (last_key.as_str(), value.trim())
+            }
+            // Continuation of a wrapped value
+            _ => (last_key.as_str(), line.trim()),
+        };
+
+        let list = || {
+            value
+                .split("  ")
+                .map(|item| item.trim())
+                .filter(|item| !item.is_empty() && *item != "None")
+                .map(|item| item.to_string())
+        };
+
+        match key {
+            "Name" => info.name = value.to_string(),
+            "Version" => info.version = Some(value.to_string()),
+            "Description" => {
+                if !info.description.is_empty() {
+                    info.description.push(' ');
+                }
+                info.description.push_str(value);
+            }
+            "Repository" => info.repository = Some(value.to_string()),
+            "URL" => info.homepage = Some(value.to_string()),
+            "Licenses" => info.license = Some(list().collect::<Vec<_>>().join(", ")),
+            "Depends On" => info.dependencies.extend(list()),
+            "Installed Size" => info.size = Some(value.to_string()),
+            "Download Size" if info.size.is_none() => info.size = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    info
+}
@@ -0,0 +1,989 @@
+//! pip backend for installing Python applications through pipx, or
+//! `pip install --user` where pipx isn't available.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use futures::future::join_all;
+use serde::Deserialize;
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::config::PipConfig;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, run};
+use super::{collect_lookups, Backend};
+
+/// The index searched when neither `PIP_INDEX_URL` nor the configuration
+/// names one.
+const DEFAULT_INDEX_URL: &str = "https://pypi.org/simple/";
+
+/// The JSON API of PyPI, which has the metadata of the latest release of a
+/// project at `<name>/json`.
+const PYPI_JSON_URL: &str = "https://pypi.org/pypi/";
+
+/// How long the downloaded project list of an index is reused.
+const INDEX_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Maximum number of search results. The simple index only has project
+/// names, so short queries match thousands of projects.
+const MAX_RESULTS: usize = 50;
+
+/// The `Accept` header for simple index pages: the JSON flavour of the
+/// simple repository API (PEP 691), or else the HTML one (PEP 503) that most
+/// mirrors serve.
+const SIMPLE_ACCEPT: &str = "application/vnd.pypi.simple.v1+json, text/html;q=0.1";
+
+/// Backend implementation for Python applications.
+///
+/// Searches the package index given by `PIP_INDEX_URL`, the same variable
+/// pip and pipx read when installing, or by `index_url` in the `[pip]`
+/// configuration table (PyPI by default).
+pub struct PipBackend {
+    /// The simple index URL, with a trailing slash.
+    index_url: String,
+}
+
+/// The tool packages are installed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Installer {
+    /// `pipx`, one isolated environment per application.
+    Pipx,
+    /// `pip install --user`.
+    Pip,
+}
+
+impl PipBackend {
+    /// Creates the backend, searching the index from the configuration
+    /// unless `PIP_INDEX_URL` is set.
+    pub fn new(config: &PipConfig) -> Self {
+        Self { index_url: index_url(config.index_url.as_deref()) }
+    }
+
+    /// Returns the installer to use, preferring pipx.
+    fn installer(&self) -> Option<Installer> {
+        if which::which("pipx").is_ok() {
+            Some(Installer::Pipx)
+        } else if which::which("pip3").is_ok() {
+            Some(Installer::Pip)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the installer, or an error naming the missing tools.
+    fn require_installer(&self) -> Result<Installer, BackendError> {
+        self.installer()
+            .ok_or_else(|| BackendError::CommandNotFound("pipx or pip3".into()))
+    }
+}
+
+#[async_trait]
+impl Backend for PipBackend {
+    fn name(&self) -> &str { "pip" }
+
+    fn exists(&self) -> bool {
+        // The index is queried with curl
+        self.installer().is_some() && which::which("curl").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching Python package index...");
+
+        let (projects, installed) = tokio::join!(
+            index_projects(&self.index_url),
+            self.list_installed(ProgressBar::hidden())
+        );
+
+        let index = &self.index_url;
+        let installed = installed.unwrap_or_default();
+
+        let results = match_projects(&projects?, query)
+            .into_iter()
+            .map(|name| {
+                let installed_version = installed
+                    .iter()
+                    .find(|i| normalize(&i.identifier) == normalize(&name))
+                    .and_then(|i| i.installed_version.clone());
+
+                SearchResult {
+                    backend: "pip".into(),
+                    name: name.clone(),
+                    identifier: name,
+                    description: format!("Python package from {}", index),
+                    version: None, // The project list doesn't have versions
+                    installed: installed_version.is_some(),
+                    installed_version,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        pb.finish_with_message("Python package search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Python applications...");
+
+        let results = match self.require_installer()? {
+            Installer::Pipx => {
+                let stdout = capture(Command::new("pipx").arg("list").arg("--json")).await?;
+                parse_pipx_list(&stdout)?
+            }
+            Installer::Pip => {
+                let stdout = capture(
+                    Command::new("pip3")
+                        .arg("list")
+                        .arg("--user")
+                        .arg("--format=json"),
+                )
+                .await?;
+                parse_pip_list(&stdout)?
+            }
+        };
+
+        pb.finish_with_message("Python listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Python upgrades...");
+
+        // Neither pipx nor the index report outdated packages, so the latest
+        // version of each installed package is looked up in the index
+        let installed = self.list_installed(ProgressBar::hidden()).await?;
+        let latest = join_all(
+            installed
+                .iter()
+                .map(|p| project_page(&self.index_url, &p.identifier)),
+        )
+        .await;
+
+        let latest = collect_lookups(latest)?;
+
+        let results = installed
+            .into_iter()
+            .zip(latest)
+            .filter_map(|(mut pkg, page)| {
+                let latest = latest_release(&page?.versions)?;
+                let current = release_key(pkg.installed_version.as_deref()?)?;
+                if release_key(&latest)? <= current {
+                    return None;
+                }
+                pkg.version = Some(latest);
+                Some(pkg)
+            })
+            .collect();
+
+        pb.finish_with_message("Python upgrade check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        // PyPI has a JSON API with the metadata of the latest release, other
+        // indexes may serve the METADATA file of each distribution
+        if self.index_url == DEFAULT_INDEX_URL {
+            let url = format!("{}{}/json", PYPI_JSON_URL, normalize(&pkg.identifier));
+            let metadata = parse_pypi_json(&capture(curl().arg(&url)).await?)?;
+            return Ok(metadata.into_info(&self.index_url));
+        }
+
+        let page = project_page(&self.index_url, &pkg.identifier).await?;
+        let version = latest_release(&page.versions).or_else(|| page.versions.last().cloned());
+
+        let file = version.as_deref().and_then(|version| {
+            page.files
+                .iter()
+                .find(|f| f.has_metadata() && file_version(&f.filename) == Some(version))
+        });
+        if let Some(file) = file {
+            let url = format!("{}.metadata", file.url);
+            let metadata = parse_metadata(&capture(curl().arg(&url)).await?);
+            return Ok(metadata.into_info(&self.index_url));
+        }
+
+        // Without metadata, only the project page is known
+        Ok(PackageInfo {
+            backend: "pip".into(),
+            name: page.name,
+            version,
+            description: pkg.description.clone(),
+            repository: Some(self.index_url.clone()),
+            ..PackageInfo::default()
+        })
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], _assume_yes: bool) -> Result<(), BackendError> {
+        // Neither pipx nor pip prompt when installing
+        match self.require_installer()? {
+            Installer::Pipx => {
+                run(Command::new("pipx")
+                    .arg("install")
+                    .args(pkgs.iter().map(|p| &p.identifier)))
+                .await
+            }
+            Installer::Pip => {
+                run(Command::new("pip3")
+                    .arg("install")
+                    .arg("--user")
+                    .args(pkgs.iter().map(|p| &p.identifier)))
+                .await
+            }
+        }
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        match self.require_installer()? {
+            // Older pipx versions take one package at a time
+            Installer::Pipx => {
+                for pkg in pkgs {
+                    run(Command::new("pipx").arg("uninstall").arg(&pkg.identifier)).await?;
+                }
+                Ok(())
+            }
+            Installer::Pip => {
+                run(Command::new("pip3")
+                    .arg("uninstall")
+                    .arg("--yes")
+                    .args(pkgs.iter().map(|p| &p.identifier)))
+                .await
+            }
+        }
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        match self.require_installer()? {
+            Installer::Pipx => {
+                for pkg in pkgs {
+                    run(Command::new("pipx").arg("upgrade").arg(&pkg.identifier)).await?;
+                }
+                Ok(())
+            }
+            Installer::Pip => {
+                run(Command::new("pip3")
+                    .arg("install")
+                    .arg("--user")
+                    .arg("--upgrade")
+                    .args(pkgs.iter().map(|p| &p.identifier)))
+                .await
+            }
+        }
+    }
+}
+
+/// Returns the simple index URL from `PIP_INDEX_URL`, or else `configured`,
+/// with a trailing slash.
+fn index_url(configured: Option<&str>) -> String {
+    let url = std::env::var("PIP_INDEX_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .or_else(|| configured.map(|url| url.to_string()))
+        .unwrap_or_else(|| DEFAULT_INDEX_URL.to_string());
+
+    if url.ends_with('/') { url } else { format!("{}/", url) }
+}
+
+/// Returns a curl command that fails on HTTP errors and follows redirects.
+fn curl() -> Command {
+    let mut cmd = Command::new("curl");
+    cmd.arg("--fail").arg("--silent").arg("--show-error").arg("--location");
+    cmd
+}
+
+/// Fetches a page of the simple index, as JSON if the index serves it.
+async fn fetch_simple(url: &str) -> Result<String, BackendError> {
+    capture(
+        curl()
+            .arg("--header")
+            .arg(format!("Accept: {}", SIMPLE_ACCEPT))
+            .arg(url),
+    )
+    .await
+}
+
+/// The project list of a simple index.
+#[derive(Deserialize)]
+struct SimpleIndex {
+    projects: Vec<SimpleProject>,
+}
+
+#[derive(Deserialize)]
+struct SimpleProject {
+    name: String,
+}
+
+/// The page of a single project in a simple index.
+#[derive(Deserialize)]
+struct ProjectPage {
+    name: String,
+    /// Every published version (PEP 700). Older indexes leave this out.
+    #[serde(default)]
+    versions: Vec<String>,
+    /// The distribution files, with absolute URLs.
+    #[serde(default)]
+    files: Vec<ProjectFile>,
+}
+
+/// A distribution file on a project page.
+#[derive(Deserialize)]
+struct ProjectFile {
+    filename: String,
+    url: String,
+    /// Whether the METADATA file is served at the file URL with `.metadata`
+    /// appended (PEP 658): `true` or a table of hashes.
+    #[serde(rename = "core-metadata")]
+    core_metadata: Option<serde_json::Value>,
+    /// The name of `core_metadata` before PEP 714.
+    #[serde(rename = "dist-info-metadata")]
+    dist_info_metadata: Option<serde_json::Value>,
+}
+
+impl ProjectFile {
+    /// Returns `true` if the index serves the METADATA file of this file.
+    fn has_metadata(&self) -> bool {
+        [&self.core_metadata, &self.dist_info_metadata]
+            .into_iter()
+            .flatten()
+            .any(|value| !matches!(value, serde_json::Value::Null | serde_json::Value::Bool(false)))
+    }
+}
+
+/// Returns the project names of the index, from a cache refreshed daily.
+///
+/// The full PyPI project list is tens of megabytes, too much to download on
+/// every search.
+async fn index_projects(index: &str) -> Result<Vec<String>, BackendError> {
+    let cache = cache_path(index);
+
+    let fresh = match &cache {
+        Some(path) => tokio::fs::metadata(path)
+            .await
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < INDEX_CACHE_TTL),
+        None => false,
+    };
+
+    let contents = match &cache {
+        Some(path) if fresh => tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| BackendError::Io(format!("{}: {}", path.display(), e)))?,
+        _ => {
+            let contents = fetch_simple(index).await?;
+            if let Some(path) = &cache {
+                // A failed write only costs a download next time
+                if let Some(dir) = path.parent() {
+                    let _ = tokio::fs::create_dir_all(dir).await;
+                }
+                let _ = tokio::fs::write(path, &contents).await;
+            }
+            contents
+        }
+    };
+
+    if !is_json(&contents) {
+        return Ok(html_links(&contents).into_iter().map(|name| name.to_string()).collect());
+    }
+
+    let index: SimpleIndex = serde_json::from_str(&contents)
+        .map_err(|e| BackendError::ParseError(format!("simple index of {}: {}", index, e)))?;
+
+    Ok(index.projects.into_iter().map(|p| p.name).collect())
+}
+
+/// Returns where the project list of `index` is cached, under
+/// `$XDG_CACHE_HOME/fi` (or `~/.cache/fi`).
+fn cache_path(index: &str) -> Option<PathBuf> {
+    let cache_home = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    // One file per index, named after its URL
+    let name: String = index
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    Some(cache_home.join("fi").join(format!("pip-{}.json", name.trim_matches('_'))))
+}
+
+/// Fetches the index page of a project.
+async fn project_page(index: &str, name: &str) -> Result<ProjectPage, BackendError> {
+    let url = format!("{}{}/", index, normalize(name));
+    let stdout = fetch_simple(&url).await?;
+
+    let mut page = if is_json(&stdout) {
+        serde_json::from_str(&stdout)
+            .map_err(|e| BackendError::ParseError(format!("{}: {}", url, e)))?
+    } else {
+        parse_html_project_page(&stdout, name)
+    };
+
+    for file in &mut page.files {
+        file.url = join_url(&url, &file.url);
+    }
+    Ok(page)
+}
+
+/// Resolves a link on the page at `base`, which may be absolute, relative
+/// to the host or relative to the page.
+fn join_url(base: &str, href: &str) -> String {
+    if href.contains("://") {
+        return href.to_string();
+    }
+
+    let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+    if let Some(href) = href.strip_prefix("//") {
+        return format!("{}://{}", scheme, href);
+    }
+
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let mut segments: Vec<&str> = if href.starts_with('/') {
+        Vec::new()
+    } else {
+        // Everything up to the last slash is the directory of the page
+        let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        dir.split('/').filter(|s| !s.is_empty()).collect()
+    };
+
+    for segment in href.split('/').filter(|s| !s.is_empty()) {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    format!("{}://{}/{}", scheme, host, segments.join("/"))
+}
+
+/// Returns `true` if a simple index page is the JSON form rather than HTML.
+fn is_json(s: &str) -> bool {
+    s.trim_start().starts_with('{')
+}
+
+/// Returns the text of the links on an HTML simple index page (PEP 503),
+/// which are the project names on the index page and the file names on a
+/// project page.
+///
+/// Example: `<a href="/simple/ripgrep-py/">ripgrep-py</a><br/>`
+fn html_links(s: &str) -> Vec<&str> {
+    html_anchors(s).into_iter().map(|(_, text)| text).collect()
+}
+
+/// Returns the opening tag and text of each link on an HTML page.
+fn html_anchors(s: &str) -> Vec<(&str, &str)> {
+    let mut anchors = Vec::new();
+    let mut rest = s;
+
+    while let Some(start) = rest.find("<a ") {
+        rest = &rest[start..];
+        let Some(text_start) = rest.find('>') else {
+            break;
+        };
+        let Some(text_end) = rest.find("</a>") else {
+            break;
+        };
+
+        let text = rest.get(text_start + 1..text_end).unwrap_or("").trim();
+        if !text.is_empty() {
+            anchors.push((&rest[..text_start], text));
+        }
+        rest = &rest[text_end + "</a>".len()..];
+    }
+
+    anchors
+}
+
+/// Returns the value of a double-quoted attribute of an HTML tag.
+fn html_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// Parses an HTML project page (PEP 503), taking the versions from the
+/// distribution file names.
+///
+/// Example:
+/// ```text
+/// <a href="../httpie-3.2.2.tar.gz#sha256=...">httpie-3.2.2.tar.gz</a><br/>
+/// <a href="../httpie-3.2.2-py3-none-any.whl#sha256=...">httpie-3.2.2-py3-none-any.whl</a><br/>
+/// ```
+fn parse_html_project_page(s: &str, name: &str) -> ProjectPage {
+    let mut versions: Vec<String> = Vec::new();
+    let mut files = Vec::new();
+
+    for (tag, file) in html_anchors(s) {
+        if let Some(version) = file_version(file)
+            && !versions.iter().any(|v| v == version)
+        {
+            versions.push(version.to_string());
+        }
+
+        let Some(href) = html_attr(tag, "href") else {
+            continue;
+        };
+        // The fragment is the hash of the file, not part of its URL
+        let url = href.split('#').next().unwrap_or(href).replace("&amp;", "&");
+        let metadata = ["data-core-metadata", "data-dist-info-metadata"]
+            .iter()
+            .find_map(|attr| html_attr(tag, attr))
+            .filter(|value| *value != "false")
+            .map(|_| serde_json::Value::Bool(true));
+
+        files.push(ProjectFile {
+            filename: file.to_string(),
+            url,
+            core_metadata: metadata,
+            dist_info_metadata: None,
+        });
+    }
+
+    ProjectPage { name: name.to_string(), versions, files }
+}
+
+/// Returns the version in the name of a distribution file.
+///
+/// Wheels are `name-version-tags.whl` with `-` escaped in the name, source
+/// distributions `name-version.tar.gz` or `.zip`.
+fn file_version(file: &str) -> Option<&str> {
+    if let Some(stem) = file.strip_suffix(".whl") {
+        return stem.split('-').nth(1);
+    }
+
+    [".tar.gz", ".zip", ".tar.bz2"]
+        .iter()
+        .find_map(|ext| file.strip_suffix(ext))
+        .and_then(|stem| stem.rsplit_once('-'))
+        .map(|(_, version)| version)
+}
+
+/// Normalizes a project name as the simple index does (PEP 503): lowercase,
+/// with runs of `-`, `_` and `.` replaced by a single `-`.
+fn normalize(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Picks the projects whose normalized name contains the query, best matches
+/// first: the exact name, then names starting with the query, then the rest,
+/// shorter names first within each group.
+fn match_projects(projects: &[String], query: &str) -> Vec<String> {
+    let query = normalize(query);
+
+    let mut matches: Vec<(u8, &String)> = projects
+        .iter()
+        .filter_map(|name| {
+            let normalized = normalize(name);
+            let rank = if normalized == query {
+                0
+            } else if normalized.starts_with(&query) {
+                1
+            } else if normalized.contains(&query) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, name))
+        })
+        .collect();
+
+    matches.sort_by(|(a_rank, a), (b_rank, b)| {
+        a_rank.cmp(b_rank).then(a.len().cmp(&b.len())).then(a.cmp(b))
+    });
+
+    matches
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, name)| name.clone())
+        .collect()
+}
+
+/// Returns the numeric release segments of a final release version, or
+/// `None` for pre-releases, development and post releases.
+fn release_key(version: &str) -> Option<Vec<u64>> {
+    // Strip an epoch, which nearly no project uses
+    let version = version.rsplit('!').next()?;
+    version.split('.').map(|n| n.parse().ok()).collect()
+}
+
+/// Returns the newest final release among `versions`.
+fn latest_release(versions: &[String]) -> Option<String> {
+    versions
+        .iter()
+        .filter_map(|v| Some((release_key(v)?, v)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, v)| v.clone())
+}
+
+/// The core metadata of a release, from the PyPI JSON API or a METADATA
+/// file.
+#[derive(Default, Deserialize)]
+struct CoreMetadata {
+    name: String,
+    version: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    license: Option<String>,
+    /// An SPDX license expression, which replaces `license` (PEP 639).
+    license_expression: Option<String>,
+    home_page: Option<String>,
+    /// Links by label, e.g. `Homepage` or `Source`.
+    project_urls: Option<HashMap<String, String>>,
+    /// Requirements, with environment markers like `; extra == "socks"`.
+    requires_dist: Option<Vec<String>>,
+    classifiers: Option<Vec<String>>,
+}
+
+impl CoreMetadata {
+    /// Converts the metadata into package details.
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - The index the release was found in.
+    fn into_info(self, repository: &str) -> PackageInfo {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty() && v != "UNKNOWN")
+        };
+
+        // Some projects put the whole license text in the license field, so
+        // only its first line is kept. Older ones only have a classifier like
+        // `License :: OSI Approved :: MIT License`.
+        let license = non_empty(self.license_expression)
+            .or_else(|| non_empty(self.license.map(|l| l.lines().next().unwrap_or("").into())))
+            .or_else(|| {
+                self.classifiers
+                    .iter()
+                    .flatten()
+                    .filter(|c| c.starts_with("License ::"))
+                    .find_map(|c| c.rsplit(" :: ").next())
+                    .map(|l| l.to_string())
+            });
+
+        let homepage = non_empty(self.home_page).or_else(|| {
+            self.project_urls.iter().flatten().find_map(|(label, url)| {
+                let label = label.to_lowercase().replace(['-', '_', ' '], "");
+                (label == "homepage").then(|| url.clone())
+            })
+        });
+
+        // Requirements of optional extras aren't installed by default
+        let dependencies = self
+            .requires_dist
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| !r.split_once(';').is_some_and(|(_, marker)| marker.contains("extra")))
+            .collect();
+
+        let description = match (non_empty(self.summary), non_empty(self.description)) {
+            (Some(summary), Some(description)) => format!("{}\n\n{}", summary, description),
+            (summary, description) => summary.or(description).unwrap_or_default(),
+        };
+
+        PackageInfo {
+            backend: "pip".into(),
+            name: self.name,
+            version: self.version,
+            description,
+            license,
+            homepage,
+            repository: Some(repository.to_string()),
+            dependencies,
+            ..PackageInfo::default()
+        }
+    }
+}
+
+/// The document served by the PyPI JSON API.
+#[derive(Deserialize)]
+struct PypiProject {
+    info: CoreMetadata,
+}
+
+/// Parses a project document of the PyPI JSON API.
+fn parse_pypi_json(s: &str) -> Result<CoreMetadata, BackendError> {
+    let project: PypiProject = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("PyPI JSON API: {}", e)))?;
+    Ok(project.info)
+}
+
+/// Parses a METADATA file (core metadata specification).
+///
+/// Headers may continue on indented lines, and the description is either the
+/// `Description` header or the body after the first blank line.
+/// Example:
+/// ```text
+/// Metadata-Version: 2.1
+/// Name: httpie
+/// Version: 3.2.2
+/// Summary: HTTPie: modern, user-friendly command-line HTTP client for the API era.
+/// Home-page: https://httpie.io/
+/// License: BSD
+/// Requires-Dist: requests[socks] >=2.22.0
+/// Requires-Dist: pytest ; extra == 'test'
+///
+/// HTTPie: human-friendly CLI HTTP client for the API era
+/// ```
+fn parse_metadata(s: &str) -> CoreMetadata {
+    let s = s.replace("\r\n", "\n");
+    let (headers, body) = s.split_once("\n\n").unwrap_or((&s, ""));
+
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    for line in headers.lines() {
+        if line.starts_with([' ', '\t']) {
+            // Older files prefix continued descriptions with `|`
+            if let Some((_, value)) = fields.last_mut() {
+                let line = line.trim_start();
+                value.push('\n');
+                value.push_str(line.strip_prefix('|').unwrap_or(line));
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            fields.push((key, value.trim().to_string()));
+        }
+    }
+
+    let mut metadata = CoreMetadata::default();
+    for (key, value) in fields {
+        match key.to_ascii_lowercase().as_str() {
+            "name" => metadata.name = value,
+            "version" => metadata.version = Some(value),
+            "summary" => metadata.summary = Some(value),
+            "description" => metadata.description = Some(value),
+            "license" => metadata.license = Some(value),
+            "license-expression" => metadata.license_expression = Some(value),
+            "home-page" => metadata.home_page = Some(value),
+            "project-url" => {
+                if let Some((label, url)) = value.split_once(',') {
+                    metadata
+                        .project_urls
+                        .get_or_insert_default()
+                        .insert(label.trim().to_string(), url.trim().to_string());
+                }
+            }
+            "requires-dist" => metadata.requires_dist.get_or_insert_default().push(value),
+            "classifier" => metadata.classifiers.get_or_insert_default().push(value),
+            _ => {}
+        }
+    }
+
+    if !body.trim().is_empty() {
+        metadata.description = Some(body.trim().to_string());
+    }
+    metadata
+}
+
+/// The document printed by `pipx list --json`.
+#[derive(Deserialize)]
+struct PipxList {
+    venvs: HashMap<String, PipxVenv>,
+}
+
+#[derive(Deserialize)]
+struct PipxVenv {
+    metadata: PipxMetadata,
+}
+
+#[derive(Deserialize)]
+struct PipxMetadata {
+    main_package: PipxPackage,
+}
+
+#[derive(Deserialize)]
+struct PipxPackage {
+    package: String,
+    package_version: String,
+    #[serde(default)]
+    apps: Vec<String>,
+}
+
+/// Parses `pipx list --json` output.
+fn parse_pipx_list(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let list: PipxList = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("pipx list JSON output: {}", e)))?;
+
+    let mut results: Vec<SearchResult> = list
+        .venvs
+        .into_values()
+        .map(|venv| {
+            let package = venv.metadata.main_package;
+            SearchResult {
+                backend: "pip".into(),
+                name: package.package.clone(),
+                identifier: package.package,
+                description: format!("Apps: {}", package.apps.join(", ")),
+                version: Some(package.package_version.clone()),
+                installed: true,
+                installed_version: Some(package.package_version),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    results.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(results)
+}
+
+/// An entry of `pip list --format=json` output.
+#[derive(Deserialize)]
+struct PipPackage {
+    name: String,
+    version: String,
+}
+
+/// Parses `pip list --format=json` output.
+fn parse_pip_list(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let packages: Vec<PipPackage> = serde_json::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("pip list JSON output: {}", e)))?;
+
+    Ok(packages
+        .into_iter()
+        .map(|package| SearchResult {
+            backend: "pip".into(),
+            name: package.name.clone(),
+            identifier: package.name,
+            description: "Installed with pip --user".into(),
+            version: Some(package.version.clone()),
+            installed: true,
+            installed_version: Some(package.version),
+            ..Default::default()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pypi_json() {
+        let json = r#"{
+  "info": {
+    "name": "httpie",
+    "version": "3.2.2",
+    "summary": "HTTPie: modern, user-friendly command-line HTTP client for the API era.",
+    "description": "HTTPie is a command-line HTTP client.",
+    "license": "BSD",
+    "license_expression": null,
+    "home_page": "",
+    "project_urls": {
+      "Homepage": "https://httpie.io/",
+      "Source": "https://github.com/httpie/cli"
+    },
+    "requires_dist": [
+      "requests[socks]>=2.22.0",
+      "pip",
+      "pytest; extra == \"test\""
+    ],
+    "classifiers": ["License :: OSI Approved :: BSD License"]
+  },
+  "releases": {}
+}"#;
+
+        let info = parse_pypi_json(json).unwrap().into_info(DEFAULT_INDEX_URL);
+        assert_eq!(info.name, "httpie");
+        assert_eq!(info.version.as_deref(), Some("3.2.2"));
+        assert_eq!(info.license.as_deref(), Some("BSD"));
+        assert_eq!(info.homepage.as_deref(), Some("https://httpie.io/"));
+        assert_eq!(info.dependencies, ["requests[socks]>=2.22.0", "pip"]);
+        assert!(info.description.starts_with("HTTPie: modern"));
+        assert!(info.description.ends_with("command-line HTTP client."));
+    }
+
+    #[test]
+    fn parses_metadata_file() {
+        let metadata = "Metadata-Version: 2.1\n\
+            Name: black\n\
+            Version: 24.4.2\n\
+            Summary: The uncompromising code formatter.\n\
+            Project-URL: Changelog, https://github.com/psf/black/blob/main/CHANGES.md\n\
+            Project-URL: Homepage, https://github.com/psf/black\n\
+            Requires-Dist: click>=8.0.0\n\
+            Requires-Dist: tomli>=1.1.0; python_version < '3.11'\n\
+            Requires-Dist: aiohttp>=3.7.4; extra == 'd'\n\
+            Classifier: License :: OSI Approved :: MIT License\n\
+            \n\
+            # Black\n";
+
+        let info = parse_metadata(metadata).into_info("https://mirror.example.com/simple/");
+        assert_eq!(info.name, "black");
+        assert_eq!(info.version.as_deref(), Some("24.4.2"));
+        assert_eq!(info.license.as_deref(), Some("MIT License"));
+        assert_eq!(info.homepage.as_deref(), Some("https://github.com/psf/black"));
+        assert_eq!(
+            info.dependencies,
+            ["click>=8.0.0", "tomli>=1.1.0; python_version < '3.11'"]
+        );
+        assert_eq!(info.description, "The uncompromising code formatter.\n\n# Black");
+    }
+
+    #[test]
+    fn parses_continued_metadata_headers() {
+        let metadata = "Name: six\n\
+            License: MIT\n\
+            \x20       Copyright (c) 2010-2020 Benjamin Peterson\n\
+            Description: Six is a Python 2 and 3 compatibility library.\n\
+            \x20       |\n\
+            \x20       |It provides utility functions.\n";
+
+        let metadata = parse_metadata(metadata);
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("Six is a Python 2 and 3 compatibility library.\n\nIt provides utility functions.")
+        );
+        assert_eq!(metadata.into_info("").license.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn finds_metadata_files_on_html_pages() {
+        let html = r#"<html><body>
+<a href="../../packages/six-1.16.0.tar.gz#sha256=1e61">six-1.16.0.tar.gz</a><br/>
+<a href="../../packages/six-1.16.0-py2.py3-none-any.whl#sha256=8abb"
+   data-dist-info-metadata="sha256=9e1c">six-1.16.0-py2.py3-none-any.whl</a><br/>
+</body></html>"#;
+
+        let page = parse_html_project_page(html, "six");
+        assert_eq!(page.versions, ["1.16.0"]);
+        assert_eq!(page.files.len(), 2);
+        assert!(!page.files[0].has_metadata());
+        assert!(page.files[1].has_metadata());
+        assert_eq!(
+            join_url("https://mirror.example.com/simple/six/", &page.files[1].url),
+            "https://mirror.example.com/packages/six-1.16.0-py2.py3-none-any.whl"
+        );
+    }
+
+    #[test]
+    fn joins_urls() {
+        let base = "https://example.com/simple/httpie/";
+        assert_eq!(
+            join_url(base, "https://files.example.org/httpie.whl"),
+            "https://files.example.org/httpie.whl"
+        );
+        assert_eq!(join_url(base, "/a/httpie.whl"), "https://example.com/a/httpie.whl");
+        assert_eq!(join_url(base, "httpie.whl"), "https://example.com/simple/httpie/httpie.whl");
+        assert_eq!(join_url(base, "//cdn.example.net/a.whl"), "https://cdn.example.net/a.whl");
+    }
+}
@@ -0,0 +1,299 @@
+//! External backends, implemented by executables speaking a JSON protocol
+//! over standard input and output.
+//!
+//! Any executable named `fi-backend-<name>` on `PATH` is picked up as a
+//! backend called `<name>`, unless a built-in backend already has that name.
+//! fi runs it once per request, writes the request as a JSON object to its
+//! standard input and reads the response from its standard output.
+//!
+//! Requests carry the protocol version and a command:
+//! ```text
+//! {"protocol": 1, "command": "search", "query": "ripgrep"}
+//! {"protocol": 1, "command": "list-installed"}
+//! {"protocol": 1, "command": "list-upgrades"}
+//! {"protocol": 1, "command": "info", "package": {...}}
+//! {"protocol": 1, "command": "install", "packages": [{...}], "assume_yes": false}
+//! {"protocol": 1, "command": "remove", "packages": [{...}]}
+//! {"protocol": 1, "command": "upgrade", "packages": [{...}]}
+//! ```
+//!
+//! Packages sent to the plugin are ones it returned earlier, with the fields
+//! below plus `backend`. Search and list commands answer with packages, where
+//! only `name` is required and `identifier` defaults to it:
+//! ```text
+//! {"packages": [{"name": "ripgrep", "identifier": "ripgrep", "description": "...",
+//!   "version": "14.1.0", "installed": true, "installed_version": "13.0.0",
+//!   "origin": "internal", "branch": "stable", "arch": "x86_64",
+//!   "metadata": {"maintainer": "tools-team"}}]}
+//! ```
+//!
+//! `info` answers with `{"info": {"name": ..., "version": ..., "description": ...,
+//! "license": ..., "homepage": ..., "repository": ..., "size": ...,
+//! "dependencies": [...]}}`, and install, remove and upgrade with `{}` or
+//! nothing at all. During those three, standard error is attached to the
+//! terminal for progress output.
+//!
+//! Failures are reported as `{"error": "message"}` with exit status 0, with
+//! an optional `"kind"` of `"permission-denied"` or `"network"`. Any other
+//! exit status is treated as the plugin crashing.
+
+use std::collections::{BTreeMap, HashSet};
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture_with_input, run_with_input};
+use super::Backend;
+
+/// Prefix of the executables that implement external backends.
+const PLUGIN_PREFIX: &str = "fi-backend-";
+
+/// Version of the protocol, sent with every request.
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Backend implementation for an external `fi-backend-<name>` executable.
+pub struct PluginBackend {
+    name: String,
+    path: PathBuf,
+}
+
+#[async_trait]
+impl Backend for PluginBackend {
+    fn name(&self) -> &str { &self.name }
+
+    fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message(format!("Searching {}...", self.name));
+
+        let response = self.query(&Request::Search { query }).await?;
+        let results = self.to_results(response.packages);
+
+        pb.finish_with_message(format!("{} search done", self.name));
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message(format!("Listing {} packages...", self.name));
+
+        let response = self.query(&Request::ListInstalled).await?;
+        let results = self.to_results(response.packages);
+
+        pb.finish_with_message(format!("{} listing done", self.name));
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message(format!("Checking {} upgrades...", self.name));
+
+        let response = self.query(&Request::ListUpgrades).await?;
+        let results = self.to_results(response.packages);
+
+        pb.finish_with_message(format!("{} upgrade check done", self.name));
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let info = self.query(&Request::Info { package: pkg }).await?.info.ok_or_else(|| {
+            BackendError::ParseError(format!("{} sent no info for {}", self.name, pkg.identifier))
+        })?;
+
+        Ok(PackageInfo {
+            backend: self.name.clone(),
+            name: info.name.unwrap_or_else(|| pkg.name.clone()),
+            version: info.version,
+            description: info.description,
+            license: info.license,
+            homepage: info.homepage,
+            repository: info.repository,
+            size: info.size,
+            dependencies: info.dependencies,
+        })
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        self.perform(&Request::Install { packages: pkgs, assume_yes }).await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        self.perform(&Request::Remove { packages: pkgs }).await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        self.perform(&Request::Upgrade { packages: pkgs }).await
+    }
+}
+
+impl PluginBackend {
+    /// Sends a search, list or info request, with standard error captured.
+    async fn query(&self, request: &Request<'_>) -> Result<Response, BackendError> {
+        let stdout = capture_with_input(&mut Command::new(&self.path), &encode(request)?).await?;
+        self.parse_response(&stdout)
+    }
+
+    /// Sends an install, remove or upgrade request, with standard error
+    /// attached to the terminal.
+    async fn perform(&self, request: &Request<'_>) -> Result<(), BackendError> {
+        let stdout = run_with_input(&mut Command::new(&self.path), &encode(request)?).await?;
+        self.parse_response(&stdout).map(|_| ())
+    }
+
+    /// Parses a response, turning a reported error into a [`BackendError`].
+    ///
+    /// Example: `{"error": "repository unreachable", "kind": "network"}`
+    fn parse_response(&self, s: &str) -> Result<Response, BackendError> {
+        if s.trim().is_empty() {
+            return Ok(Response::default());
+        }
+
+        let response: Response = serde_json::from_str(s)
+            .map_err(|e| BackendError::ParseError(format!("{} response: {}", self.name, e)))?;
+
+        let Some(error) = response.error else {
+            return Ok(response);
+        };
+        let message = format!("{}: {}", self.name, error);
+        Err(match response.kind.as_deref() {
+            Some("permission-denied") => BackendError::PermissionDenied(message),
+            Some("network") => BackendError::NetworkError(message),
+            _ => BackendError::Plugin(message),
+        })
+    }
+
+    fn to_results(&self, packages: Vec<PluginPackage>) -> Vec<SearchResult> {
+        packages
+            .into_iter()
+            .map(|package| SearchResult {
+                backend: self.name.clone(),
+                identifier: package.identifier.unwrap_or_else(|| package.name.clone()),
+                name: package.name,
+                description: package.description,
+                version: package.version,
+                installed: package.installed || package.installed_version.is_some(),
+                installed_version: package.installed_version,
+                origin: package.origin,
+                branch: package.branch,
+                arch: package.arch,
+                metadata: package.metadata,
+            })
+            .collect()
+    }
+}
+
+/// A request, serialized with the protocol version.
+#[derive(Serialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+enum Request<'a> {
+    Search { query: &'a str },
+    ListInstalled,
+    ListUpgrades,
+    Info { package: &'a SearchResult },
+    Install { packages: &'a [SearchResult], assume_yes: bool },
+    Remove { packages: &'a [SearchResult] },
+    Upgrade { packages: &'a [SearchResult] },
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    protocol: u32,
+    #[serde(flatten)]
+    request: &'a Request<'a>,
+}
+
+fn encode(request: &Request<'_>) -> Result<String, BackendError> {
+    serde_json::to_string(&Envelope { protocol: PROTOCOL_VERSION, request })
+        .map_err(|e| BackendError::ParseError(e.to_string()))
+}
+
+/// A response; which fields are set depends on the command.
+#[derive(Deserialize, Default)]
+struct Response {
+    #[serde(default)]
+    packages: Vec<PluginPackage>,
+    info: Option<PluginInfo>,
+    error: Option<String>,
+    kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PluginPackage {
+    name: String,
+    identifier: Option<String>,
+    #[serde(default)]
+    description: String,
+    version: Option<String>,
+    #[serde(default)]
+    installed: bool,
+    installed_version: Option<String>,
+    origin: Option<String>,
+    branch: Option<String>,
+    arch: Option<String>,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct PluginInfo {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    description: String,
+    license: Option<String>,
+    homepage: Option<String>,
+    repository: Option<String>,
+    size: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// Finds the `fi-backend-<name>` executables on `PATH`.
+///
+/// When several directories provide the same name, the first one wins, as it
+/// would for a command run from the shell.
+pub fn discover() -> Vec<PluginBackend> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return vec![];
+    };
+
+    let mut seen = HashSet::new();
+    let mut plugins = Vec::new();
+
+    for dir in std::env::split_paths(&path) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+
+        let mut found: Vec<(String, PathBuf)> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let name = entry.file_name().to_str()?.strip_prefix(PLUGIN_PREFIX)?.to_string();
+                let path = entry.path();
+
+                let metadata = std::fs::metadata(&path).ok()?;
+                let executable = metadata.is_file() && metadata.permissions().mode() & 0o111 != 0;
+                (executable && !name.is_empty()).then_some((name, path))
+            })
+            .collect();
+        found.sort();
+
+        for (name, path) in found {
+            if seen.insert(name.clone()) {
+                plugins.push(PluginBackend { name, path });
+            }
+        }
+    }
+
+    plugins
+}
@@ -0,0 +1,349 @@
+//! Snap backend for searching and installing snaps from the Snap Store.
+
+use futures::future::try_join_all;
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, run};
+use super::{mark_installed, root_command, Backend};
+
+/// Backend implementation for the Snap package manager.
+pub struct SnapBackend;
+
+#[async_trait]
+impl Backend for SnapBackend {
+    fn name(&self) -> &str { "snap" }
+
+    fn exists(&self) -> bool {
+        which::which("snap").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching Snap...");
+
+        let mut find = Command::new("snap");
+        find.arg("find").arg(query);
+
+        // List installed snaps alongside the search to mark matches
+        let (output, installed) =
+            tokio::join!(capture(&mut find), self.list_installed(ProgressBar::hidden()));
+
+        let mut results = match output {
+            Ok(stdout) => parse_snap_find(&stdout),
+            Err(BackendError::CommandFailed { stderr, .. })
+                if stderr.contains("No matching snaps") => vec![],
+            Err(e) => return Err(e),
+        };
+        mark_installed(&mut results, &installed.unwrap_or_default(), |r| &r.identifier);
+
+        pb.finish_with_message("Snap search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing snaps...");
+
+        let stdout = capture(Command::new("snap").arg("list")).await?;
+        let results = parse_snap_list(&stdout);
+
+        pb.finish_with_message("Snap listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking snap refreshes...");
+
+        let mut refresh = Command::new("snap");
+        refresh.arg("refresh").arg("--list");
+
+        // The installed versions are not part of `refresh --list` output
+        let (output, installed) =
+            tokio::join!(capture(&mut refresh), self.list_installed(ProgressBar::hidden()));
+
+        let mut results: Vec<SearchResult> = parse_snap_refresh_list(&output?);
+        mark_installed(&mut results, &installed.unwrap_or_default(), |r| &r.identifier);
+
+        pb.finish_with_message("Snap refresh check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        let stdout = capture(Command::new("snap").arg("info").arg(&pkg.identifier)).await?;
+
+        let info = parse_snap_info(&stdout);
+        if info.name.is_empty() {
+            return Err(BackendError::ParseError(format!(
+                "no snap details for {} in snap info output",
+                pkg.identifier
+            )));
+        }
+
+        Ok(info)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        // Classic snaps must be installed with --classic, and snap only
+        // accepts that flag when installing a single snap
+        let classic = try_join_all(pkgs.iter().map(|p| is_classic(&p.identifier))).await?;
+        let (classic, strict): (Vec<_>, Vec<_>) =
+            pkgs.iter().zip(classic).partition(|(_, classic)| *classic);
+
+        if !strict.is_empty() {
+            run(root_command("snap", assume_yes)
+                .arg("install")
+                .args(strict.iter().map(|(p, _)| &p.identifier)))
+            .await?;
+        }
+
+        for (pkg, _) in classic {
+            println!("{} uses classic confinement and can access the whole system", pkg.name);
+            run(root_command("snap", assume_yes)
+                .arg("install")
+                .arg("--classic")
+                .arg(&pkg.identifier))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("snap", false)
+            .arg("remove")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("snap", false)
+            .arg("refresh")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+}
+
+/// Checks `snap info` to see whether a snap needs classic confinement.
+async fn is_classic(name: &str) -> Result<bool, BackendError> {
+    let stdout = capture(Command::new("snap").arg("info").arg(name)).await?;
+    Ok(parse_snap_confinement(&stdout))
+}
+
+/// Splits a line of whitespace aligned columns into its first `n` fields
+/// and the rest of the line, which may itself contain spaces.
+fn split_columns(line: &str, n: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(n);
+    let mut rest = line.trim_start();
+
+    for _ in 0..n {
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start();
+    }
+
+    Some((fields, rest.trim_end()))
+}
+
+/// Parses `snap find` output.
+///
+/// Output format: `Name  Version  Publisher  Notes  Summary` columns after a
+/// header line. The summary may contain spaces.
+/// Example:
+/// ```text
+/// Name     Version  Publisher  Notes    Summary
+/// firefox  129.0.2  mozilla✓   -        Mozilla Firefox web browser
+/// code     1.92.0   vscode✓    classic  Code editing. Redefined.
+/// ```
+fn parse_snap_find(s: &str) -> Vec<SearchResult> {
+    s.lines()
+        .skip_while(|line| !line.starts_with("Name"))
+        .skip(1)
+        .filter_map(|line| {
+            let (fields, summary) = split_columns(line, 4)?;
+            let [name, version, _publisher, _notes] = fields.as_slice() else {
+                return None;
+            };
+
+            Some(SearchResult {
+                backend: "snap".into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: summary.to_string(),
+                version: Some(version.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `snap list` output.
+///
+/// Output format: `Name  Version  Rev  Tracking  Publisher  Notes` columns
+/// after a header line.
+/// Example:
+/// ```text
+/// Name     Version   Rev   Tracking       Publisher   Notes
+/// core22   20240111  1122  latest/stable  canonical✓  base
+/// firefox  129.0.2   4793  latest/stable  mozilla✓    -
+/// ```
+fn parse_snap_list(s: &str) -> Vec<SearchResult> {
+    s.lines()
+        .skip_while(|line| !line.starts_with("Name"))
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, version, _rev, tracking, ..] = fields.as_slice() else {
+                return None;
+            };
+
+            Some(SearchResult {
+                backend: "snap".into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: format!("Tracking {}", tracking),
+                version: Some(version.to_string()),
+                installed: true,
+                installed_version: Some(version.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `snap refresh --list` output.
+///
+/// Output format: `Name  Version  Rev  Size  Publisher  Notes` columns after
+/// a header line, with the version being the one available. When nothing
+/// needs refreshing snap prints "All snaps up to date." instead.
+/// Example:
+/// ```text
+/// Name     Version  Rev   Size   Publisher  Notes
+/// firefox  130.0    4848  283MB  mozilla✓   -
+/// ```
+fn parse_snap_refresh_list(s: &str) -> Vec<SearchResult> {
+    s.lines()
+        .skip_while(|line| !line.starts_with("Name"))
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, version, ..] = fields.as_slice() else {
+                return None;
+            };
+
+            Some(SearchResult {
+                backend: "snap".into(),
+                name: name.to_string(),
+                identifier: name.to_string(),
+                description: "Refresh from the Snap Store".into(),
+                version: Some(version.to_string()),
+                installed: true,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Parses `snap info` output.
+///
+/// Output format: YAML-like `key: value` lines. The description is a block
+/// indented below `description: |`, and the channels are listed below
+/// `channels:` as `track/risk: version date (revision) size notes`.
+/// Example:
+/// ```text
+/// name:      code
+/// summary:   Code editing. Redefined.
+/// publisher: Visual Studio Code (vscode✓)
+/// store-url: https://snapcraft.io/code
+/// license:   unset
+/// description: |
+///   Visual Studio Code is a new choice of tool that combines the simplicity
+///   of a code editor with what developers need.
+/// snap-id: Ht0aUgqKkDeSNKl7YF2JbhqU3TqZuUca
+/// channels:
+///   latest/stable:    1.92.0 2024-08-07 (166) 341MB classic
+/// ```
+fn parse_snap_info(s: &str) -> PackageInfo {
+    let mut info = PackageInfo {
+        backend: "snap".into(),
+        ..PackageInfo::default()
+    };
+    let mut summary = "";
+    let mut section = "";
+
+    for line in s.lines() {
+        // Indented lines belong to the current section
+        if let Some(value) = line.strip_prefix("  ") {
+            let value = value.trim();
+            match section {
+                "description" => {
+                    if !info.description.is_empty() {
+                        info.description.push(if value.is_empty() { '\n' } else { ' ' });
+                    }
+                    info.description.push_str(value);
+                }
+                "channels" if info.version.is_none() => {
+                    // The first channel is the default one
+                    if let Some((_, details)) = value.split_once(':') {
+                        let mut details = details.split_whitespace();
+                        info.version = details.next().map(|v| v.to_string());
+                        info.size = details.nth(2).map(|v| v.to_string());
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        section = key;
+
+        match key {
+            "name" => info.name = value.to_string(),
+            "summary" => summary = value,
+            "license" if value != "unset" => info.license = Some(value.to_string()),
+            "store-url" => info.homepage = Some(value.to_string()),
+            "publisher" => info.repository = Some(format!("Snap Store ({})", value)),
+            _ => {}
+        }
+    }
+
+    if info.description.is_empty() {
+        info.description = summary.to_string();
+    }
+
+    info
+}
+
+/// Checks `snap info` output for classic confinement.
+///
+/// The notes at the end of each channel line (and of the `installed:` line)
+/// read `classic` for snaps that need classic confinement.
+fn parse_snap_confinement(s: &str) -> bool {
+    let mut in_channels = false;
+
+    for line in s.lines() {
+        if !line.starts_with(' ') {
+            in_channels = line.starts_with("channels:");
+            if !line.starts_with("installed:") {
+                continue;
+            }
+        } else if !in_channels {
+            continue;
+        }
+
+        if line.split_whitespace().last() == Some("classic") {
+            return true;
+        }
+    }
+
+    false
+}
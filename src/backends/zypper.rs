@@ -0,0 +1,559 @@
+//! Zypper backend for searching and installing openSUSE packages.
+
+use serde::Deserialize;
+use tokio::process::Command;
+use indicatif::ProgressBar;
+use async_trait::async_trait;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
+use crate::models::result::SearchResult;
+use crate::utils::command::{capture, capture_allowing, run};
+use super::{mark_installed, root_command, Backend};
+
+/// Exit code zypper uses when a search or info query matches nothing.
+const ZYPPER_EXIT_INF_CAP_NOT_FOUND: i32 = 104;
+
+/// Backend implementation for the Zypper package manager.
+pub struct ZypperBackend;
+
+#[async_trait]
+impl Backend for ZypperBackend {
+    fn name(&self) -> &str { "zypper" }
+
+    fn exists(&self) -> bool {
+        which::which("zypper").is_ok()
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        pb: ProgressBar,
+    ) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Searching Zypper...");
+
+        let mut search = zypper_xml();
+        search.arg("search").arg("--type").arg("package").arg(query);
+
+        // The search output only has the install status, the installed
+        // versions come from a listing
+        let (output, installed) = tokio::join!(
+            capture_allowing(&mut search, &[ZYPPER_EXIT_INF_CAP_NOT_FOUND]),
+            self.list_installed(ProgressBar::hidden())
+        );
+
+        let mut results = parse_zypper_search(&output?)?;
+        mark_installed(&mut results, &installed.unwrap_or_default(), |r| &r.identifier);
+
+        pb.finish_with_message("Zypper search done");
+        Ok(results)
+    }
+
+    async fn list_installed(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Listing Zypper packages...");
+
+        let stdout = capture(
+            zypper_xml()
+                .arg("search")
+                .arg("--installed-only")
+                .arg("--details")
+                .arg("--type")
+                .arg("package"),
+        )
+        .await?;
+
+        let results = parse_zypper_search(&stdout)?
+            .into_iter()
+            .filter(|r| r.installed)
+            .map(|mut r| {
+                r.installed_version = r.version.clone();
+                r
+            })
+            .collect();
+
+        pb.finish_with_message("Zypper listing done");
+        Ok(results)
+    }
+
+    async fn list_upgrades(&self, pb: ProgressBar) -> Result<Vec<SearchResult>, BackendError> {
+        pb.set_message("Checking Zypper updates...");
+
+        let stdout = capture(zypper_xml().arg("list-updates").arg("--type").arg("package")).await?;
+        let results = parse_zypper_updates(&stdout)?;
+
+        pb.finish_with_message("Zypper update check done");
+        Ok(results)
+    }
+
+    async fn info(&self, pkg: &SearchResult) -> Result<PackageInfo, BackendError> {
+        // `zypper info` has no structured output
+        let stdout = capture(
+            Command::new("zypper")
+                .arg("--non-interactive")
+                .arg("info")
+                .arg("--requires")
+                .arg(&pkg.identifier),
+        )
+        .await?;
+
+        let info = parse_zypper_info(&stdout);
+        if info.name.is_empty() {
+            return Err(BackendError::ParseError(format!(
+                "no package details for {} in zypper info output",
+                pkg.identifier
+            )));
+        }
+
+        Ok(info)
+    }
+
+    async fn install(&self, pkgs: &[SearchResult], assume_yes: bool) -> Result<(), BackendError> {
+        run(root_command("zypper", assume_yes)
+            .arg("install")
+            .arg("-y")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn remove(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("zypper", false)
+            .arg("remove")
+            .arg("-y")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+
+    async fn upgrade(&self, pkgs: &[SearchResult]) -> Result<(), BackendError> {
+        run(root_command("zypper", false)
+            .arg("update")
+            .arg("-y")
+            .args(pkgs.iter().map(|p| &p.identifier)))
+        .await
+    }
+}
+
+/// Builds a non-interactive zypper command with XML output.
+fn zypper_xml() -> Command {
+    let mut command = Command::new("zypper");
+    command.arg("--xmlout").arg("--non-interactive");
+    command
+}
+
+/// The `<stream>` document zypper prints with `--xmlout`.
+///
+/// Progress and `<message>` elements are ignored.
+#[derive(Deserialize)]
+struct ZypperStream {
+    #[serde(rename = "search-result")]
+    search_result: Option<ZypperSearchResult>,
+    #[serde(rename = "update-status")]
+    update_status: Option<ZypperUpdateStatus>,
+}
+
+#[derive(Deserialize)]
+struct ZypperSearchResult {
+    #[serde(rename = "solvable-list", default)]
+    solvable_list: ZypperSolvableList,
+}
+
+#[derive(Deserialize, Default)]
+struct ZypperSolvableList {
+    #[serde(rename = "solvable", default)]
+    solvables: Vec<ZypperSolvable>,
+}
+
+/// A package in search results. `edition` is only present with `--details`.
+#[derive(Deserialize)]
+struct ZypperSolvable {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@status", default)]
+    status: String,
+    #[serde(rename = "@summary", default)]
+    summary: String,
+    #[serde(rename = "@edition")]
+    edition: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ZypperUpdateStatus {
+    #[serde(rename = "update-list", default)]
+    update_list: ZypperUpdateList,
+}
+
+#[derive(Deserialize, Default)]
+struct ZypperUpdateList {
+    #[serde(rename = "update", default)]
+    updates: Vec<ZypperUpdate>,
+}
+
+#[derive(Deserialize)]
+struct ZypperUpdate {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@edition")]
+    edition: String,
+    #[serde(rename = "@edition-old")]
+    edition_old: Option<String>,
+    #[serde(default)]
+    summary: String,
+    source: Option<ZypperSource>,
+}
+
+#[derive(Deserialize)]
+struct ZypperSource {
+    #[serde(rename = "@alias")]
+    alias: String,
+}
+
+/// Deserializes zypper's `--xmlout` output.
+fn parse_zypper_stream(s: &str) -> Result<ZypperStream, BackendError> {
+    quick_xml::de::from_str(s)
+        .map_err(|e| BackendError::ParseError(format!("zypper XML output: {}", e)))
+}
+
+/// Parses `zypper --xmlout search` output.
+///
+/// Example:
+/// ```text
+/// <?xml version='1.0'?>
+/// <stream>
+/// <message type="info">Loading repository data...</message>
+/// <search-result version="0.0">
+/// <solvable-list>
+/// <solvable status="installed" name="ripgrep" summary="A search tool" kind="package"/>
+/// <solvable status="not-installed" name="ripgrep-zsh" summary="Zsh completion" kind="package"/>
+/// </solvable-list>
+/// </search-result>
+/// </stream>
+/// ```
+///
+/// The status is `other-version` when a different version than the listed
+/// one is installed, which still counts as installed.
+fn parse_zypper_search(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let Some(search_result) = parse_zypper_stream(s)?.search_result else {
+        // No <search-result> at all means nothing matched
+        return Ok(vec![]);
+    };
+
+    let mut results: Vec<SearchResult> = Vec::new();
+    for solvable in search_result.solvable_list.solvables {
+        // With --details a package is listed once per version and arch
+        if results.iter().any(|r| r.identifier == solvable.name) {
+            continue;
+        }
+
+        results.push(SearchResult {
+            backend: "zypper".into(),
+            name: solvable.name.clone(),
+            identifier: solvable.name,
+            description: solvable.summary,
+            version: solvable.edition,
+            installed: matches!(solvable.status.as_str(), "installed" | "other-version"),
+            ..Default::default()
+        });
+    }
+
+    Ok(results)
+}
+
+/// Parses `zypper --xmlout list-updates` output.
+///
+/// Example:
+/// ```text
+/// <stream>
+/// <update-status version="0.6">
+/// <update-list>
+/// <update kind="package" name="curl" edition="8.6.0-4.1" edition-old="8.6.0-3.1">
+/// <summary>A Tool for Transferring Data from URLs</summary>
+/// <source url="https://download.opensuse.org/tumbleweed" alias="repo-update"/>
+/// </update>
+/// </update-list>
+/// </update-status>
+/// </stream>
+/// ```
+fn parse_zypper_updates(s: &str) -> Result<Vec<SearchResult>, BackendError> {
+    let Some(update_status) = parse_zypper_stream(s)?.update_status else {
+        return Ok(vec![]);
+    };
+
+    Ok(update_status
+        .update_list
+        .updates
+        .into_iter()
+        .map(|update| SearchResult {
+            backend: "zypper".into(),
+            name: update.name.clone(),
+            identifier: update.name,
+            description: match &update.source {
+                Some(source) => format!("Update from {}", source.alias),
+                None => update.summary,
+            },
+            version: Some(update.edition),
+            installed: true,
+            installed_version: update.edition_old,
+            origin: update.source.map(|source| source.alias),
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// Parses `zypper info --requires` output.
+///
+/// Output format: `Key : Value` lines. The description and the requirements
+/// follow their heading on indented lines.
+/// Example:
+/// ```text
+/// Information for package ripgrep:
+/// --------------------------------
+/// Repository     : Main Repository (OSS)
+/// Name           : ripgrep
+/// Version        : 14.1.1-1.1
+/// Installed Size : 4.6 MiB
+/// Source package : ripgrep-14.1.1-1.1.src
+/// Upstream URL   : https://github.com/BurntSushi/ripgrep
+/// Summary        : A search tool that combines the usability of ag with grep
+/// Description    :
+///     ripgrep is a line-oriented search tool.
+/// Requires       : [2]
+///     libc.so.6()(64bit)
+///     libpcre2-8.so.0()(64bit)
+/// ```
+fn parse_zypper_info(s: &str) -> PackageInfo {
+    let mut info = PackageInfo {
+        backend: "zypper".into(),
+        ..PackageInfo::default()
+    };
+    let mut summary = "";
+    let mut section = "";
+
+    for line in s.lines() {
+        // Indented and blank lines belong to the current section
+        if line.starts_with(char::is_whitespace) || line.is_empty() {
+            let value = line.trim();
+            match section {
+                // Blank lines separate paragraphs, other lines are rewrapped
+                "Description" => {
+                    let continues =
+                        !info.description.is_empty() && !info.description.ends_with('\n');
+                    if value.is_empty() {
+                        if continues {
+                            info.description.push_str("\n\n");
+                        }
+                    } else {
+                        if continues {
+                            info.description.push(' ');
+                        }
+                        info.description.push_str(value);
+                    }
+                }
+                "Requires" if !value.is_empty() => info.dependencies.push(value.to_string()),
+                _ => {}
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(" : ").or_else(|| {
+            line.strip_suffix(':').map(|key| (key, ""))
+        }) else {
+            section = "";
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        section = key;
+
+        match key {
+            "Name" => info.name = value.to_string(),
+            "Version" => info.version = Some(value.to_string()),
+            "Repository" => info.repository = Some(value.to_string()),
+            "Installed Size" => info.size = Some(value.to_string()),
+            "Upstream URL" => info.homepage = Some(value.to_string()),
+            "License" => info.license = Some(value.to_string()),
+            "Summary" => summary = value,
+            _ => {}
+        }
+    }
+
+    let description = info.description.trim();
+    info.description = if description.is_empty() {
+        summary.to_string()
+    } else {
+        description.to_string()
+    };
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEARCH: &str = r#"<?xml version='1.0'?>
+<stream>
+<message type="info">Loading repository data...</message>
+<message type="info">Reading installed packages...</message>
+<search-result version="0.0">
+<solvable-list>
+<solvable status="installed" name="ripgrep"
+    summary="A search tool that combines the usability of ag with grep" kind="package"/>
+<solvable status="other-version" name="ripgrep-bash-completion"
+    summary="Bash Completion for ripgrep" kind="package"/>
+<solvable status="not-installed" name="ripgrep-zsh-completion"
+    summary="Zsh Completion for ripgrep" kind="package"/>
+</solvable-list>
+</search-result>
+</stream>
+"#;
+
+    const SEARCH_INSTALLED: &str = r#"<?xml version='1.0'?>
+<stream>
+<message type="info">Loading repository data...</message>
+<message type="info">Reading installed packages...</message>
+<search-result version="0.0">
+<solvable-list>
+<solvable status="installed" name="curl" summary="A Tool for Transferring Data from URLs"
+    kind="package" edition="8.6.0-3.1" arch="x86_64" repository="(System Packages)"/>
+<solvable status="installed" name="curl" summary="A Tool for Transferring Data from URLs"
+    kind="package" edition="8.6.0-3.1" arch="i586" repository="(System Packages)"/>
+<solvable status="installed" name="ripgrep"
+    summary="A search tool that combines the usability of ag with grep" kind="package"
+    edition="14.1.1-1.1" arch="x86_64" repository="(System Packages)"/>
+</solvable-list>
+</search-result>
+</stream>
+"#;
+
+    const LIST_UPDATES: &str = r#"<?xml version='1.0'?>
+<stream>
+<message type="info">Loading repository data...</message>
+<message type="info">Reading installed packages...</message>
+<update-status version="0.6">
+<update-list>
+<update kind="package" name="curl" edition="8.6.0-4.1" arch="x86_64" edition-old="8.6.0-3.1">
+<summary>A Tool for Transferring Data from URLs</summary>
+<description>curl is a client to get documents and files from URLs.</description>
+<license></license>
+<source url="https://download.opensuse.org/update/tumbleweed" alias="repo-update"/>
+</update>
+<update kind="package" name="vim" edition="9.1.0330-1.1" arch="x86_64" edition-old="9.1.0111-1.1">
+<summary>Vi IMproved</summary>
+<description>Vim is a text editor.</description>
+<license></license>
+</update>
+</update-list>
+</update-status>
+</stream>
+"#;
+
+    const NO_MATCHES: &str = r#"<?xml version='1.0'?>
+<stream>
+<message type="info">Loading repository data...</message>
+<message type="info">Reading installed packages...</message>
+<message type="info">No matching items found.</message>
+</stream>
+"#;
+
+    const INFO: &str = "Loading repository data...
+Reading installed packages...
+
+
+Information for package ripgrep:
+--------------------------------
+Repository     : Main Repository (OSS)
+Name           : ripgrep
+Version        : 14.1.1-1.1
+Arch           : x86_64
+Vendor         : openSUSE
+Installed Size : 4.6 MiB
+Installed      : Yes
+Status         : up-to-date
+Source package : ripgrep-14.1.1-1.1.src
+Upstream URL   : https://github.com/BurntSushi/ripgrep
+License        : MIT AND Unlicense
+Summary        : A search tool that combines the usability of ag with grep
+Description    :
+    ripgrep is a line-oriented search tool that recursively searches
+    the current directory for a regex pattern.
+
+    It respects gitignore rules.
+Requires       : [2]
+    libc.so.6()(64bit)
+    libpcre2-8.so.0()(64bit)
+";
+
+    #[test]
+    fn parses_search() {
+        let results = parse_zypper_search(SEARCH).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.identifier.as_str()).collect();
+        assert_eq!(
+            names,
+            ["ripgrep", "ripgrep-bash-completion", "ripgrep-zsh-completion"]
+        );
+        assert_eq!(
+            results[0].description,
+            "A search tool that combines the usability of ag with grep"
+        );
+        assert!(results[0].installed);
+        assert!(results[1].installed);
+        assert!(!results[2].installed);
+        assert_eq!(results[0].version, None);
+    }
+
+    #[test]
+    fn parses_installed_search_once_per_package() {
+        let results = parse_zypper_search(SEARCH_INSTALLED).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.identifier.as_str()).collect();
+        assert_eq!(names, ["curl", "ripgrep"]);
+        assert_eq!(results[0].version.as_deref(), Some("8.6.0-3.1"));
+        assert!(results.iter().all(|r| r.installed));
+    }
+
+    #[test]
+    fn parses_list_updates() {
+        let results = parse_zypper_updates(LIST_UPDATES).unwrap();
+        assert_eq!(results.len(), 2);
+
+        let curl = &results[0];
+        assert_eq!(curl.identifier, "curl");
+        assert_eq!(curl.version.as_deref(), Some("8.6.0-4.1"));
+        assert_eq!(curl.installed_version.as_deref(), Some("8.6.0-3.1"));
+        assert_eq!(curl.origin.as_deref(), Some("repo-update"));
+        assert_eq!(curl.description, "Update from repo-update");
+
+        let vim = &results[1];
+        assert_eq!(vim.origin, None);
+        assert_eq!(vim.description, "Vi IMproved");
+    }
+
+    #[test]
+    fn parses_info() {
+        let info = parse_zypper_info(INFO);
+        assert_eq!(info.name, "ripgrep");
+        assert_eq!(info.version.as_deref(), Some("14.1.1-1.1"));
+        assert_eq!(info.repository.as_deref(), Some("Main Repository (OSS)"));
+        assert_eq!(info.size.as_deref(), Some("4.6 MiB"));
+        assert_eq!(info.homepage.as_deref(), Some("https://github.com/BurntSushi/ripgrep"));
+        assert_eq!(info.license.as_deref(), Some("MIT AND Unlicense"));
+        assert_eq!(
+            info.description,
+            "ripgrep is a line-oriented search tool that recursively searches \
+             the current directory for a regex pattern.\n\nIt respects gitignore rules."
+        );
+        assert_eq!(info.dependencies, ["libc.so.6()(64bit)", "libpcre2-8.so.0()(64bit)"]);
+    }
+
+    #[tokio::test]
+    async fn treats_exit_code_104_as_no_matches() {
+        // zypper exits with 104 after printing a stream without results
+        let mut search = Command::new("sh");
+        search
+            .arg("-c")
+            .arg("printf '%s' \"$0\"; exit 104")
+            .arg(NO_MATCHES);
+
+        let output = capture_allowing(&mut search, &[ZYPPER_EXIT_INF_CAP_NOT_FOUND])
+            .await
+            .unwrap();
+        assert!(parse_zypper_search(&output).unwrap().is_empty());
+        assert!(parse_zypper_updates(&output).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+//! Command-line argument definitions for the fi binary.
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::ui::output::OutputFormat;
+
+/// Unified package manager for Linux.
+///
+/// Searches DNF, APT, Pacman, Flatpak, Cargo and more at once and installs
+/// from whichever source you pick.
+#[derive(Debug, Parser)]
+#[command(name = "fi", version, about, long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Top-level subcommands.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Search all package managers and optionally install a result
+    Search {
+        /// The search term
+        query: String,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Install a package, choosing between the backends that provide it
+    ///
+    /// The package may be given as `<backend>:<identifier>` (e.g.
+    /// `flatpak:org.mozilla.firefox`) to skip the choice. Together with
+    /// `--yes` this installs without a terminal UI.
+    Install {
+        /// Name of the package to install, optionally prefixed with `<backend>:`
+        package: String,
+
+        /// Install from this backend instead of asking
+        #[arg(long, short)]
+        backend: Option<String>,
+
+        /// Install from this repository or remote (e.g. a Flatpak remote),
+        /// for packages a backend offers from several
+        #[arg(long)]
+        origin: Option<String>,
+
+        /// Install this branch (e.g. Flatpak's `stable` or `beta`)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Don't prompt: fail if the choice of backend is ambiguous, and
+        /// answer yes to the package manager's prompts
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Remove an installed package
+    Remove {
+        /// Name of the package to remove
+        package: String,
+    },
+
+    /// Show details about a package
+    Info {
+        /// Name of the package to inspect
+        package: String,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// List detected package managers, or installed packages
+    List {
+        /// List installed packages instead of package managers
+        #[arg(long)]
+        installed: bool,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Upgrade installed packages across all package managers
+    Update,
+}
+
+/// Flags selecting machine-readable output instead of the TUI.
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    /// Print results as a JSON array instead of opening the TUI
+    #[arg(long, conflicts_with = "ndjson")]
+    pub json: bool,
+
+    /// Stream results as newline-delimited JSON, one object per line
+    #[arg(long)]
+    pub ndjson: bool,
+}
+
+impl OutputArgs {
+    /// Returns the requested machine-readable format, or `None` for the
+    /// interactive interface.
+    pub fn format(&self) -> Option<OutputFormat> {
+        if self.json {
+            Some(OutputFormat::Json)
+        } else if self.ndjson {
+            Some(OutputFormat::Ndjson)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! User configuration, read from `$XDG_CONFIG_HOME/fi/config`.
+//!
+//! The file is TOML and every setting is optional:
+//! ```text
+//! [backends]
+//! order = ["flatpak", "dnf"]   # searched and listed first, the rest follow
+//! disabled = ["snap"]
+//!
+//! [flatpak]
+//! remote = "flathub"
+//! installation = "user"       # or "system", the default
+//!
+//! [pip]
+//! index_url = "https://pypi.org/simple/"
+//!
+//! [appimage]
+//! catalog = "https://example.org/appimages/catalog.json"
+//!
+//! [ui]
+//! max_section_height = 12
+//!
+//! [ui.colors]
+//! accent = "green"      # a color name, "#rrggbb" or a palette index like "42"
+//! installed = "cyan"
+//! error = "red"
+//! status = "yellow"
+//! ```
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::error::FiError;
+
+/// The smallest section height that still shows a border and one package.
+const MIN_SECTION_HEIGHT: u16 = 3;
+
+/// The parsed configuration file, with defaults for everything it leaves out.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Which backends are used, and in what order.
+    pub backends: BackendsConfig,
+    /// Flatpak settings.
+    pub flatpak: FlatpakConfig,
+    /// pip settings.
+    pub pip: PipConfig,
+    /// AppImage settings.
+    pub appimage: AppImageConfig,
+    /// Terminal UI settings.
+    pub ui: UiConfig,
+}
+
+/// The `[backends]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackendsConfig {
+    /// Backends to put first, in this order. The others keep their default
+    /// order after them.
+    pub order: Vec<String>,
+    /// Backends that are never used, even when installed.
+    pub disabled: Vec<String>,
+}
+
+/// The `[flatpak]` table.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlatpakConfig {
+    /// The remote used for packages that don't say which remote they come
+    /// from.
+    pub remote: String,
+    /// Where new applications are installed.
+    pub installation: FlatpakInstallation,
+}
+
+impl Default for FlatpakConfig {
+    fn default() -> Self {
+        Self {
+            remote: "flathub".into(),
+            installation: FlatpakInstallation::System,
+        }
+    }
+}
+
+/// A Flatpak installation, per user or system-wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlatpakInstallation {
+    /// `--user`, in the home directory, without root.
+    User,
+    /// `--system`, shared by all users.
+    System,
+}
+
+/// The `[pip]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipConfig {
+    /// The simple index searched for packages. `PIP_INDEX_URL` takes
+    /// precedence, as it does for pip itself.
+    pub index_url: Option<String>,
+}
+
+/// The `[appimage]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppImageConfig {
+    /// The catalog, a path or an http(s) URL. `FI_APPIMAGE_CATALOG` takes
+    /// precedence.
+    pub catalog: Option<String>,
+}
+
+/// The `[ui]` table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// The most rows a backend's section takes, borders included.
+    pub max_section_height: u16,
+    /// Colors of the terminal UI.
+    pub colors: Colors,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            max_section_height: 12,
+            colors: Colors::default(),
+        }
+    }
+}
+
+/// The `[ui.colors]` table.
+///
+/// The defaults are palette colors, so they follow the terminal's theme.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Colors {
+    /// Section borders, titles and the highlighted package.
+    pub accent: Color,
+    /// Installed packages.
+    pub installed: Color,
+    /// Failed backends.
+    pub error: Color,
+    /// Messages in the status line.
+    pub status: Color,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Self {
+            accent: Color::Indexed(2),
+            installed: Color::Indexed(6),
+            error: Color::Indexed(1),
+            status: Color::Indexed(3),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration file, or the defaults if there is none.
+    ///
+    /// # Returns
+    ///
+    /// The validated configuration, or [`FiError::Config`] naming the file if
+    /// it can't be read or contains invalid settings.
+    pub fn load() -> Result<Config, FiError> {
+        let Some(path) = config_path() else {
+            return Ok(Config::default());
+        };
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(FiError::Config(format!("{}: {}", path.display(), e))),
+        };
+
+        Config::parse(&contents)
+            .map_err(|e| FiError::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parses and validates the contents of a configuration file.
+    fn parse(s: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(s).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks the settings that parse but make no sense.
+    fn validate(&self) -> Result<(), String> {
+        for (key, names) in [
+            ("backends.order", &self.backends.order),
+            ("backends.disabled", &self.backends.disabled),
+        ] {
+            let mut seen = HashSet::new();
+            if let Some(name) = names.iter().find(|name| !seen.insert(name.as_str())) {
+                return Err(format!("{} lists {} twice", key, name));
+            }
+        }
+
+        if let Some(name) = self
+            .backends
+            .order
+            .iter()
+            .find(|name| self.backends.disabled.contains(name))
+        {
+            return Err(format!("{} is both ordered and disabled in [backends]", name));
+        }
+
+        if self.flatpak.remote.trim().is_empty() {
+            return Err("flatpak.remote must not be empty".into());
+        }
+
+        if self.ui.max_section_height < MIN_SECTION_HEIGHT {
+            return Err(format!(
+                "ui.max_section_height must be at least {}",
+                MIN_SECTION_HEIGHT
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the backends named in `[backends]` against `known`, the
+    /// backends fi knows about on this machine, installed or not.
+    ///
+    /// A name fi doesn't know may be an external backend that is only
+    /// installed on some machines, so a configuration can be shared between
+    /// them. Such names are returned for the caller to warn about instead of
+    /// being rejected, unless they can't name an external backend at all.
+    ///
+    /// # Returns
+    ///
+    /// The unknown names, or [`FiError::Config`] for a name that isn't a
+    /// valid backend name.
+    pub fn check_backend_names(&self, known: &[&str]) -> Result<Vec<&str>, FiError> {
+        let mut unknown = Vec::new();
+
+        for name in self.backends.order.iter().chain(&self.backends.disabled) {
+            if known.contains(&name.as_str()) {
+                continue;
+            }
+
+            // The names `<backend>:<package>` specs accept
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                return Err(FiError::Config(format!(
+                    "invalid backend name {:?} in [backends], expected one of: {}",
+                    name,
+                    known.join(", ")
+                )));
+            }
+            unknown.push(name.as_str());
+        }
+
+        Ok(unknown)
+    }
+}
+
+/// Returns `$XDG_CONFIG_HOME/fi/config`, or `~/.config/fi/config`.
+fn config_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(dir.join("fi").join("config"))
+}
@@ -3,26 +3,141 @@ use std::fmt;
 /// Errors that can occur during package search and installation.
 #[derive(Debug)]
 pub enum FiError {
-    /// Backend installation failed
+    /// One or more backends failed to install
     InstallationFailed(String),
+    /// One or more backends failed to remove
+    RemovalFailed(String),
+    /// One or more backends failed to upgrade
+    UpgradeFailed(String),
     /// Backend not found for selected package
     BackendNotFound(String),
+    /// No backend provides the requested package
+    PackageNotFound(String),
     /// TUI error
     TuiError(String),
+    /// Writing machine-readable output failed
+    OutputFailed(String),
     /// Invalid command line arguments
     InvalidArguments(String),
+    /// The configuration file can't be read or is invalid
+    Config(String),
+    /// A backend operation failed
+    Backend(BackendError),
+}
+
+impl FiError {
+    /// Returns the process exit code that corresponds to this error.
+    ///
+    /// Usage errors exit with `2` (matching the argument parser), everything
+    /// else exits with `1`.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FiError::InvalidArguments(_) => 2,
+            _ => 1,
+        }
+    }
 }
 
 impl fmt::Display for FiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            FiError::InstallationFailed(msg) => write!(f, "Installation failed: {}", msg),
+            FiError::InstallationFailed(backends) => {
+                write!(f, "Installation failed for: {}", backends)
+            }
+            FiError::RemovalFailed(backends) => write!(f, "Removal failed for: {}", backends),
+            FiError::UpgradeFailed(backends) => write!(f, "Upgrade failed for: {}", backends),
             FiError::BackendNotFound(backend) => write!(f, "Backend not found: {}", backend),
+            FiError::PackageNotFound(name) => write!(f, "Package not found: {}", name),
             FiError::TuiError(msg) => write!(f, "TUI error: {}", msg),
+            FiError::OutputFailed(msg) => write!(f, "Failed to write output: {}", msg),
             FiError::InvalidArguments(msg) => write!(f, "Invalid arguments: {}", msg),
+            FiError::Config(msg) => write!(f, "Invalid configuration: {}", msg),
+            FiError::Backend(err) => write!(f, "{}", err),
         }
     }
 }
 
 impl std::error::Error for FiError {}
 
+impl From<BackendError> for FiError {
+    fn from(err: BackendError) -> Self {
+        FiError::Backend(err)
+    }
+}
+
+/// Errors reported by a package manager backend.
+#[derive(Debug, Clone)]
+pub enum BackendError {
+    /// The package manager's executable could not be found
+    CommandNotFound(String),
+    /// The command exited unsuccessfully
+    CommandFailed {
+        /// The command line that failed
+        command: String,
+        /// The exit code, or `None` if the command was killed by a signal
+        code: Option<i32>,
+        /// The captured standard error, empty if it went to the terminal
+        stderr: String,
+    },
+    /// The command's output could not be understood
+    ParseError(String),
+    /// The command needs privileges fi doesn't have
+    PermissionDenied(String),
+    /// The package manager couldn't reach its repositories
+    NetworkError(String),
+    /// The command didn't finish in time
+    Timeout(String),
+    /// Reading or writing a file the backend manages failed
+    Io(String),
+    /// An external backend reported a failure
+    Plugin(String),
+    /// Only some pending upgrades were selected on a backend that can only
+    /// upgrade everything at once; names the ones left out
+    PartialUpgrade(String),
+}
+
+impl BackendError {
+    /// Returns a one-line summary suitable for a TUI section title.
+    pub fn summary(&self) -> &'static str {
+        match self {
+            BackendError::CommandNotFound(_) => "command not found",
+            BackendError::CommandFailed { .. } => "command failed",
+            BackendError::ParseError(_) => "unreadable output",
+            BackendError::PermissionDenied(_) => "permission denied",
+            BackendError::NetworkError(_) => "network error",
+            BackendError::Timeout(_) => "timed out",
+            BackendError::Io(_) => "file error",
+            BackendError::Plugin(_) => "plugin error",
+            BackendError::PartialUpgrade(_) => "partial upgrade",
+        }
+    }
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendError::CommandNotFound(cmd) => write!(f, "Command not found: {}", cmd),
+            BackendError::CommandFailed { command, code, stderr } => {
+                match code {
+                    Some(code) => write!(f, "`{}` exited with code {}", command, code)?,
+                    None => write!(f, "`{}` was terminated by a signal", command)?,
+                }
+                match stderr.trim().lines().last() {
+                    Some(line) => write!(f, ": {}", line),
+                    None => Ok(()),
+                }
+            }
+            BackendError::ParseError(msg) => write!(f, "Failed to parse output: {}", msg),
+            BackendError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            BackendError::NetworkError(msg) => write!(f, "Network error: {}", msg),
+            BackendError::Timeout(cmd) => write!(f, "Timed out: {}", cmd),
+            BackendError::Io(msg) => write!(f, "I/O error: {}", msg),
+            BackendError::Plugin(msg) => write!(f, "Plugin error: {}", msg),
+            BackendError::PartialUpgrade(msg) => {
+                write!(f, "Partial upgrades are not supported, also select: {}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BackendError {}
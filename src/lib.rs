@@ -1,66 +1,486 @@
 //! # fi - Unified Package Manager
 //!
 //! A unified package search and installation tool for Linux that searches across
-//! multiple package managers (DNF, Flatpak, Cargo) and provides a TUI interface
-//! to select and install packages.
+//! multiple package managers (DNF, APT, Pacman, Flatpak, Cargo, ...) and provides a
+//! TUI interface to select and install packages.
 
 pub mod backends;
+pub mod cli;
+pub mod config;
 pub mod error;
 pub mod models;
 pub mod ui;
+mod utils;
+
+use std::io::IsTerminal;
+
+use indicatif::ProgressBar;
 
 use backends::{get_available_backends, Backend};
-use error::FiError;
+use cli::{Cli, Command};
+use config::{Config, UiConfig};
+use error::{BackendError, FiError};
+use models::info::PackageInfo;
 use models::result::SearchResult;
-use ui::progress::run_search_with_progress;
-use ui::tui::{run_tui, AppState};
+use ui::output::{for_each_backend, print_items, print_results, OutputFormat};
+use ui::progress::{
+    run_list_installed_with_progress, run_list_upgrades_with_progress, run_search_with_progress,
+    BackendResults,
+};
+use ui::tui::{run_tui, Action, AppState, Section};
 
-/// Runs the main application workflow: search, display, and optionally install.
+/// Runs the subcommand selected on the command line.
 ///
 /// # Arguments
 ///
-/// * `query` - The search query string
+/// * `cli` - The parsed command line arguments
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` if the operation completed successfully, or an error if
 /// something went wrong during the process.
-pub async fn run(query: &str) -> Result<(), FiError> {
-    // Load available backends (dnf, flatpak, cargo, etc.)
-    let backends = get_available_backends();
+pub async fn run(cli: Cli) -> Result<(), FiError> {
+    let config = Config::load()?;
+
+    match cli.command {
+        Command::Search { query, output } => search(&query, output.format(), &config).await,
+        Command::Install { package, backend, origin, branch, yes } => {
+            install(
+                &package,
+                backend.as_deref(),
+                origin.as_deref(),
+                branch.as_deref(),
+                yes,
+                &config,
+            )
+            .await
+        }
+        Command::Remove { package } => remove(&package, &config).await,
+        Command::Info { package, output } => info(&package, output.format(), &config).await,
+        Command::List { installed, output } => list(installed, output.format(), &config).await,
+        Command::Update => update(&config).await,
+    }
+}
+
+/// Searches every backend and lets the user pick a result to install, or
+/// prints the results when `output` selects a machine-readable format.
+pub async fn search(
+    query: &str,
+    output: Option<OutputFormat>,
+    config: &Config,
+) -> Result<(), FiError> {
+    let backends = load_backends(config, output)?;
+
+    if let Some(format) = output {
+        return print_results(&backends, format, |b| b.search(query, ProgressBar::hidden())).await;
+    }
+
+    // Perform async search on all available backends
+    let results = run_search_with_progress(query, &backends).await;
+
+    select_and_apply(build_sections(results), backends, Action::Install, &config.ui).await
+}
+
+/// Installs a package by name.
+///
+/// The backend comes from a `<backend>:` prefix on `package` or from
+/// `backend`. Without either, the user chooses between the backends that
+/// provide an exact match in the TUI, unless there is no choice to make or
+/// `assume_yes` is set (or no terminal is attached), in which case an
+/// ambiguous match is an error. `origin` and `branch` narrow the matches
+/// down for backends that offer a package from several remotes or branches.
+pub async fn install(
+    package: &str,
+    backend: Option<&str>,
+    origin: Option<&str>,
+    branch: Option<&str>,
+    assume_yes: bool,
+    config: &Config,
+) -> Result<(), FiError> {
+    let (backend, package) = match (parse_package_spec(package), backend) {
+        ((Some(prefix), _), Some(flag)) if prefix != flag => {
+            return Err(FiError::InvalidArguments(format!(
+                "conflicting backends: {} and --backend {}",
+                prefix, flag
+            )));
+        }
+        ((prefix, name), flag) => (prefix.or(flag), name),
+    };
+
+    let mut backends = load_backends(config, None)?;
+    if let Some(name) = backend {
+        backends.retain(|b| b.name() == name);
+        if backends.is_empty() {
+            return Err(FiError::BackendNotFound(name.to_string()));
+        }
+    }
+
+    let interactive = !assume_yes && std::io::stdout().is_terminal();
+    let mut results = find_exact_matches(package, &backends, !interactive).await?;
+
+    results.retain(|r| {
+        origin.is_none_or(|origin| r.origin.as_deref() == Some(origin))
+            && branch.is_none_or(|branch| r.branch.as_deref() == Some(branch))
+    });
+    if results.is_empty() {
+        let mut source = String::new();
+        if let Some(origin) = origin {
+            source.push_str(&format!(" from {}", origin));
+        }
+        if let Some(branch) = branch {
+            source.push_str(&format!(" on branch {}", branch));
+        }
+        return Err(FiError::PackageNotFound(format!("{}{}", package, source)));
+    }
+
+    // With several candidates, prefer a single exact identifier match
+    if results.len() > 1 {
+        let by_identifier: Vec<SearchResult> = results
+            .iter()
+            .filter(|r| r.identifier == package)
+            .cloned()
+            .collect();
+        if by_identifier.len() == 1 {
+            results = by_identifier;
+        }
+    }
+
+    // Without a terminal to choose on, builds for other architectures give
+    // way to the one for this machine, as they do in the package manager
+    if results.len() > 1 && !interactive {
+        results = prefer_native_arch(results);
+    }
+
+    if results.len() > 1 && interactive {
+        let sections = group_results_by_backend(&results, &backends);
+        return select_and_apply(sections, backends, Action::Install, &config.ui).await;
+    }
+
+    let [selected] = results.as_slice() else {
+        // The same backend may offer the package from several remotes or
+        // for several architectures
+        let providers: Vec<String> = results
+            .iter()
+            .map(|r| {
+                let mut provider = r.backend.clone();
+                if let Some(origin) = &r.origin {
+                    provider.push_str(&format!(" from {}", origin));
+                }
+                if let Some(branch) = &r.branch {
+                    provider.push_str(&format!(" on branch {}", branch));
+                }
+                if let Some(arch) = &r.arch {
+                    provider.push_str(&format!(" for {}", arch));
+                }
+                provider
+            })
+            .collect();
+        return Err(FiError::InvalidArguments(format!(
+            "{} is provided by several sources ({}); pick one with --backend, --origin \
+             or --branch, or run without --yes",
+            package,
+            providers.join(", ")
+        )));
+    };
+
+    if selected.installed {
+        println!("{} is already installed via {}.", selected.name, selected.backend);
+        return Ok(());
+    }
+
+    apply_batch(Action::Install, std::slice::from_ref(selected), &backends, assume_yes).await
+}
+
+/// Drops the builds for other architectures of packages that also have a
+/// build for this machine.
+fn prefer_native_arch(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let native: Vec<SearchResult> = results
+        .iter()
+        .filter(|r| r.arch.is_some() && r.is_native_arch())
+        .cloned()
+        .collect();
+
+    results
+        .into_iter()
+        .filter(|r| r.is_native_arch() || !native.iter().any(|n| n.same_package(r)))
+        .collect()
+}
+
+/// Architectures that may follow a package name as in `foo.i686`.
+const ARCH_SUFFIXES: &[&str] = &[
+    "x86_64", "i686", "i586", "i386", "aarch64", "armv7hl", "ppc64le", "s390x", "riscv64",
+    "noarch",
+];
+
+/// Strips a known architecture suffix from `name.arch`, leaving the name to
+/// search for.
+fn strip_arch_suffix(package: &str) -> &str {
+    match package.rsplit_once('.') {
+        Some((name, arch)) if !name.is_empty() && ARCH_SUFFIXES.contains(&arch) => name,
+        _ => package,
+    }
+}
+
+/// Splits a `<backend>:<identifier>` package spec.
+///
+/// Returns `(None, spec)` if there is no backend prefix.
+fn parse_package_spec(spec: &str) -> (Option<&str>, &str) {
+    match spec.split_once(':') {
+        Some((backend, identifier))
+            if !backend.is_empty()
+                && !identifier.is_empty()
+                && backend.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') =>
+        {
+            (Some(backend), identifier)
+        }
+        _ => (None, spec),
+    }
+}
+
+/// Removes a package by name, letting the user choose between the backends
+/// that have it installed.
+///
+/// Candidates come from the installed packages rather than a search, so a
+/// package that is no longer published (a crate installed from git, an
+/// AppImage dropped from the catalog) can still be removed.
+pub async fn remove(package: &str, config: &Config) -> Result<(), FiError> {
+    let backends = load_backends(config, None)?;
+    let (installed, failure) = merge_results(run_list_installed_with_progress(&backends).await);
+    let results = keep_exact_matches(package, installed, failure)?;
+    let sections = group_results_by_backend(&results, &backends);
+
+    select_and_apply(sections, backends, Action::Remove, &config.ui).await
+}
+
+/// Prints details about a package from every backend that provides it,
+/// either human-readable or in the machine-readable format `output`.
+pub async fn info(
+    package: &str,
+    output: Option<OutputFormat>,
+    config: &Config,
+) -> Result<(), FiError> {
+    let backends = load_backends(config, output)?;
+    let results = find_exact_matches(package, &backends, output.is_some()).await?;
+
+    let mut found: Vec<(&SearchResult, PackageInfo)> = Vec::new();
+    for result in &results {
+        let Some(backend) = backends.iter().find(|b| b.name() == result.backend) else {
+            continue;
+        };
+
+        match backend.info(result).await {
+            Ok(info) => found.push((result, info)),
+            Err(e) => eprintln!("{} ({}): {}", result.name, result.backend, e),
+        }
+    }
+
+    if let Some(format) = output {
+        let infos: Vec<PackageInfo> = found.into_iter().map(|(_, info)| info).collect();
+        return print_items(&infos, format);
+    }
+
+    for (i, (result, info)) in found.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_info(info, result);
+    }
+
+    Ok(())
+}
+
+/// Lists the detected package managers, or with `installed` set, shows the
+/// installed packages of every backend in the TUI. A machine-readable
+/// `output` format prints the same data instead.
+pub async fn list(
+    installed: bool,
+    output: Option<OutputFormat>,
+    config: &Config,
+) -> Result<(), FiError> {
+    let backends = load_backends(config, output)?;
+
+    if !installed {
+        let names: Vec<&str> = backends.iter().map(|b| b.name()).collect();
+        if let Some(format) = output {
+            return print_items(&names, format);
+        }
+        for name in names {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    if let Some(format) = output {
+        return print_results(&backends, format, |b| b.list_installed(ProgressBar::hidden())).await;
+    }
+
+    let results = run_list_installed_with_progress(&backends).await;
+
+    // Everything listed is already installed, so only removal applies
+    let mut app = AppState::new(build_sections(results), config.ui.clone());
+    app.action = Action::Remove;
+    run_tui(&mut app, &backends).map_err(|e| FiError::TuiError(e.to_string()))?;
+
+    if !app.selected_results.is_empty() && app.action == Action::Remove {
+        apply_batch(Action::Remove, &app.selected_results, &backends, false).await?;
+    }
+
+    Ok(())
+}
+
+/// Checks every backend for pending upgrades and applies the ones the user
+/// marks in the TUI.
+pub async fn update(config: &Config) -> Result<(), FiError> {
+    let backends = load_backends(config, None)?;
+    let upgrades = run_list_upgrades_with_progress(&backends).await;
+
+    // Failed backends are still shown so the user sees why they're missing
+    if upgrades.iter().all(|(_, r)| r.as_ref().is_ok_and(|items| items.is_empty())) {
+        println!("Everything is up to date.");
+        return Ok(());
+    }
+
+    select_and_apply(build_sections(upgrades), backends, Action::Upgrade, &config.ui).await
+}
+
+/// Loads the available backends, failing if none are installed.
+///
+/// Configuration warnings go to stderr, except with a machine-readable
+/// `output` format, whose consumers only expect the data.
+fn load_backends(
+    config: &Config,
+    output: Option<OutputFormat>,
+) -> Result<Vec<Box<dyn Backend>>, FiError> {
+    // Load available backends (dnf, apt, pacman, flatpak, cargo, etc.)
+    let (backends, warnings) = get_available_backends(config)?;
+
+    if output.is_none() {
+        for warning in warnings {
+            eprintln!("warning: {}", warning);
+        }
+    }
 
     if backends.is_empty() {
         return Err(FiError::InvalidArguments(
-            "No package managers found. Please install at least one: dnf, flatpak, or cargo".into(),
+            "No package managers found. Please install at least one: dnf, zypper, apt, \
+                 pacman, nix, flatpak, snap, brew, cargo, go, pipx, or npm"
+                .into(),
         ));
     }
 
-    // Perform async search on all available backends
-    let results = run_search_with_progress(&query, &backends).await;
+    Ok(backends)
+}
 
-    // Group results by backend for the TUI
-    let sections = group_results_by_backend(&results, &backends);
+/// Searches every backend for `package` and keeps only exact matches.
+///
+/// A package given as `name.arch` is searched for by name and matches only
+/// the build for that architecture.
+///
+/// With `quiet` set the search runs without progress indicators and failed
+/// backends are reported on stderr. Otherwise the failure is left on the
+/// backend's spinner, and returned if no backend found the package.
+async fn find_exact_matches(
+    package: &str,
+    backends: &Vec<Box<dyn Backend>>,
+    quiet: bool,
+) -> Result<Vec<SearchResult>, FiError> {
+    // `name.arch` picks one build of a package, but backends search by name
+    let query = strip_arch_suffix(package);
+
+    let (results, failure) = if quiet {
+        let mut results = Vec::new();
+        for_each_backend(backends, |b| b.search(query, ProgressBar::hidden()), |found| {
+            results.extend(found);
+            Ok(())
+        })
+        .await?;
+        (results, None)
+    } else {
+        merge_results(run_search_with_progress(query, backends).await)
+    };
+
+    keep_exact_matches(package, results, failure)
+}
+
+/// Keeps the results that are exact matches for `package`.
+///
+/// # Returns
+///
+/// The matches, or `failure` if there are none and a backend failed,
+/// otherwise [`FiError::PackageNotFound`].
+fn keep_exact_matches(
+    package: &str,
+    results: Vec<SearchResult>,
+    failure: Option<BackendError>,
+) -> Result<Vec<SearchResult>, FiError> {
+    let results: Vec<SearchResult> = results
+        .into_iter()
+        .filter(|r| r.matches_name(package))
+        .collect();
+
+    if results.is_empty() {
+        return Err(match failure {
+            Some(e) => FiError::Backend(e),
+            None => FiError::PackageNotFound(package.to_string()),
+        });
+    }
+
+    Ok(results)
+}
+
+/// Merges per-backend results into one list, along with the last failure.
+fn merge_results(results: BackendResults) -> (Vec<SearchResult>, Option<BackendError>) {
+    let mut merged = Vec::new();
+    let mut failure = None;
+    for (_, result) in results {
+        match result {
+            Ok(found) => merged.extend(found),
+            Err(e) => failure = Some(e),
+        }
+    }
+    (merged, failure)
+}
 
+/// Shows the sections in the TUI and applies the chosen action to whichever
+/// packages the user picks.
+async fn select_and_apply(
+    sections: Vec<Section>,
+    backends: Vec<Box<dyn Backend>>,
+    action: Action,
+    ui: &UiConfig,
+) -> Result<(), FiError> {
     // Create the application state
-    let mut app = AppState::new(sections);
+    let mut app = AppState::new(sections, ui.clone());
+    app.action = action;
+
+    // Upgrades start out fully marked so Enter applies everything
+    if action == Action::Upgrade {
+        app.mark_all();
+    }
+
+    // Installing picks one build of a package, so builds for other
+    // architectures share its row; removals and upgrades list every build
+    if action == Action::Install {
+        app.group_arches();
+    }
 
     // Run the ratatui interface
-    run_tui(&mut app).map_err(|e| FiError::TuiError(e.to_string()))?;
+    run_tui(&mut app, &backends).map_err(|e| FiError::TuiError(e.to_string()))?;
 
-    // If the user selected a package, install it
-    if let Some(selected) = app.selected_result {
-        install_package(&selected, backends).await?;
+    // Install, remove or upgrade whatever the user confirmed
+    if app.selected_results.is_empty() {
+        return Ok(());
     }
 
-    Ok(())
+    apply_batch(app.action, &app.selected_results, &backends, false).await
 }
 
 /// Groups search results by their backend.
 fn group_results_by_backend(
     results: &[SearchResult],
     backends: &[Box<dyn Backend>],
-) -> Vec<(String, Vec<SearchResult>)> {
-    let mut sections: Vec<(String, Vec<SearchResult>)> = Vec::new();
+) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
 
     for backend in backends {
         let name = backend.name().to_string();
@@ -71,34 +491,122 @@ fn group_results_by_backend(
             .cloned()
             .collect();
 
-        sections.push((name, items));
+        sections.push(Section { backend: name, items, error: None });
     }
 
     sections
 }
 
-/// Installs a package using the appropriate backend.
-async fn install_package(
-    selected: &SearchResult,
-    backends: Vec<Box<dyn Backend>>,
+/// Turns per-backend results into TUI sections, keeping the error of each
+/// backend that failed.
+fn build_sections(results: BackendResults) -> Vec<Section> {
+    results
+        .into_iter()
+        .map(|(backend, result)| match result {
+            Ok(items) => Section { backend, items, error: None },
+            Err(e) => Section { backend, items: Vec::new(), error: Some(e) },
+        })
+        .collect()
+}
+
+/// Prints package details in a `Label  value` layout, followed by the
+/// backend-specific metadata of the search result they were fetched for.
+fn print_info(info: &PackageInfo, result: &SearchResult) {
+    println!("{} ({})", info.name, info.backend);
+
+    for (label, value) in info.fields() {
+        println!("  {:<12}{}", label, value);
+    }
+
+    for (key, value) in &result.metadata {
+        println!("  {:<12}{}", key, value);
+    }
+
+    if !info.dependencies.is_empty() {
+        println!("  {:<12}{}", "Depends on", info.dependencies.join(", "));
+    }
+
+    if !info.description.is_empty() {
+        println!();
+        for line in info.description.lines() {
+            println!("  {}", line);
+        }
+    }
+}
+
+/// Applies `action` to packages backend by backend and prints a summary.
+///
+/// Each backend receives its packages in a single transaction, so DNF gets
+/// one `dnf install a b c` instead of a prompt per package. A failing
+/// backend does not stop the others; the error lists every backend that
+/// failed. `assume_yes` is passed on to installs.
+async fn apply_batch(
+    action: Action,
+    packages: &[SearchResult],
+    backends: &[Box<dyn Backend>],
+    assume_yes: bool,
 ) -> Result<(), FiError> {
+    let (verb, noun, past) = match action {
+        Action::Install => ("Installing", "Installation", "installed"),
+        Action::Remove => ("Removing", "Removal", "removed"),
+        Action::Upgrade => ("Upgrading", "Upgrade", "upgraded"),
+    };
+
+    if let Some(pkg) = packages
+        .iter()
+        .find(|p| !backends.iter().any(|b| b.name() == p.backend))
+    {
+        return Err(FiError::BackendNotFound(pkg.backend.clone()));
+    }
+
+    let mut summary: Vec<(&str, usize, Result<(), BackendError>)> = Vec::new();
+
     for backend in backends {
-        if backend.name() == selected.backend {
-            println!(
-                "Installing {} via {}...",
-                selected.name, backend.name()
-            );
+        let pkgs: Vec<SearchResult> = packages
+            .iter()
+            .filter(|p| p.backend == backend.name())
+            .cloned()
+            .collect();
+
+        if pkgs.is_empty() {
+            continue;
+        }
 
-            backend
-                .install(selected)
-                .await
-                .map_err(|e| FiError::InstallationFailed(e))?;
+        println!(
+            "{} {} package(s) via {}...",
+            verb, pkgs.len(), backend.name()
+        );
+
+        let result = match action {
+            Action::Install => backend.install(&pkgs, assume_yes).await,
+            Action::Remove => backend.remove(&pkgs).await,
+            Action::Upgrade => backend.upgrade(&pkgs).await,
+        };
+        summary.push((backend.name(), pkgs.len(), result));
+    }
 
-            println!("Installation complete.");
-            return Ok(());
+    println!();
+    println!("{} summary:", noun);
+
+    let mut failed = Vec::new();
+    for (name, count, result) in summary {
+        match result {
+            Ok(()) => println!("  {}: {} {} package(s)", name, past, count),
+            Err(e) => {
+                println!("  {}: failed ({})", name, e);
+                failed.push(name);
+            }
         }
     }
 
-    Err(FiError::BackendNotFound(selected.backend.clone()))
-}
+    if failed.is_empty() {
+        return Ok(());
+    }
 
+    let failed = failed.join(", ");
+    Err(match action {
+        Action::Install => FiError::InstallationFailed(failed),
+        Action::Remove => FiError::RemovalFailed(failed),
+        Action::Upgrade => FiError::UpgradeFailed(failed),
+    })
+}
@@ -2,16 +2,16 @@
 //!
 //! Command-line interface for the fi package manager.
 
+use clap::Parser;
+use fi::cli::Cli;
 use fi::run;
 
 #[tokio::main]
 async fn main() {
-    let query = std::env::args()
-        .nth(1)
-        .expect("Usage: fi <search term>");
+    let cli = Cli::parse();
 
-    if let Err(e) = run(&query).await {
+    if let Err(e) = run(cli).await {
         eprintln!("Error: {}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
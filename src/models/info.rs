@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// Detailed information about a single package, as reported by its backend.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageInfo {
+    /// The name of the backend that provided the details.
+    pub backend: String,
+    /// The name of the package.
+    pub name: String,
+    /// The version the details refer to, if available.
+    pub version: Option<String>,
+    /// The full, possibly multi-line, description.
+    pub description: String,
+    /// The license expression, if available.
+    pub license: Option<String>,
+    /// The project homepage, if available.
+    pub homepage: Option<String>,
+    /// Where the package comes from (e.g., DNF repository, Flatpak remote,
+    /// source repository URL).
+    pub repository: Option<String>,
+    /// Human-readable download or installed size, if available.
+    pub size: Option<String>,
+    /// Packages or runtimes this package depends on.
+    pub dependencies: Vec<String>,
+}
+
+impl PackageInfo {
+    /// Returns the populated single-line fields as (label, value) pairs, in
+    /// display order.
+    pub fn fields(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Version", self.version.as_deref()),
+            ("License", self.license.as_deref()),
+            ("Homepage", self.homepage.as_deref()),
+            ("Repository", self.repository.as_deref()),
+            ("Size", self.size.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?)))
+        .collect()
+    }
+}
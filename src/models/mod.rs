@@ -1,4 +1,4 @@
 //! Data models for package search results and related structures.
 
+pub mod info;
 pub mod result;
-
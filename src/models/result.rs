@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Represents a package search result from any backend.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SearchResult {
     /// The name of the backend that found this package (e.g., "dnf", "flatpak").
     pub backend: String,
@@ -13,5 +15,68 @@ pub struct SearchResult {
     pub description: String,
     /// The version of the package, if available.
     pub version: Option<String>,
+    /// Whether the package is already installed through this backend.
+    #[serde(default)]
+    pub installed: bool,
+    /// The installed version, if the package is installed and the backend
+    /// reports it.
+    #[serde(default)]
+    pub installed_version: Option<String>,
+    /// The repository or remote the package comes from, for backends that
+    /// can install the same package from several (e.g. a Flatpak remote).
+    #[serde(default)]
+    pub origin: Option<String>,
+    /// The branch of the package, for backends that install several
+    /// branches side by side (e.g. Flatpak's `stable` and `beta`).
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// The architecture of the package, for backends that offer several
+    /// (e.g. `x86_64` and `i686` builds of a DNF package).
+    #[serde(default)]
+    pub arch: Option<String>,
+    /// Further backend-specific details, keyed by short lowercase names
+    /// (e.g. the `source` a crate was installed from).
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl SearchResult {
+    /// Returns `true` if this result is an exact match for `name`, either by
+    /// identifier, by identifier and architecture (`name.arch`) or
+    /// (case-insensitively) by display name.
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.identifier == name
+            || self.name.eq_ignore_ascii_case(name)
+            || self.arch.as_ref().is_some_and(|arch| {
+                name.strip_suffix(arch.as_str()).and_then(|n| n.strip_suffix('.'))
+                    == Some(self.identifier.as_str())
+            })
+    }
+
+    /// Returns `true` if `other` is the same package from the same source,
+    /// possibly built for a different architecture.
+    pub fn same_package(&self, other: &SearchResult) -> bool {
+        self.backend == other.backend
+            && self.identifier == other.identifier
+            && self.origin == other.origin
+            && self.branch == other.branch
+    }
+
+    /// Returns `true` if the package runs on this machine without emulation
+    /// or a multilib setup: it is built for this machine's architecture, or
+    /// doesn't depend on one.
+    pub fn is_native_arch(&self) -> bool {
+        match self.arch.as_deref() {
+            None | Some("noarch") => true,
+            Some(arch) => arch == native_arch(),
+        }
+    }
 }
 
+/// Returns the architecture fi was built for, in the naming RPM uses.
+fn native_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "i686",
+        arch => arch,
+    }
+}
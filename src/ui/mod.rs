@@ -1,4 +1,6 @@
-//! User interface components for the TUI and progress indicators.
+//! User interface components for the TUI, progress indicators and
+//! machine-readable output.
 
+pub mod output;
 pub mod progress;
 pub mod tui;
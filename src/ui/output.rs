@@ -0,0 +1,111 @@
+//! Machine-readable JSON output for scripts and editor integrations.
+//!
+//! Output goes to stdout without progress indicators so it can be piped
+//! straight into other tools.
+
+use std::io::{self, Write};
+
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::Serialize;
+
+use crate::backends::Backend;
+use crate::error::{BackendError, FiError};
+use crate::models::result::SearchResult;
+
+/// Machine-readable output formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A single JSON array, printed once everything is collected.
+    Json,
+    /// One JSON object per line, printed as soon as it is available.
+    Ndjson,
+}
+
+/// The future returned by a backend operation.
+pub type BackendFuture<'a> = BoxFuture<'a, Result<Vec<SearchResult>, BackendError>>;
+
+/// Runs `op` on every backend concurrently without progress indicators,
+/// passing each backend's results to `sink` as soon as that backend finishes.
+///
+/// Backends that fail are reported on stderr and otherwise skipped, so one
+/// broken package manager doesn't hide the results of the others.
+///
+/// # Arguments
+///
+/// * `backends` - List of backends to query
+/// * `op` - The backend operation, e.g. `|b| b.search(query, ProgressBar::hidden())`
+/// * `sink` - Called once per successful backend with its results
+pub async fn for_each_backend<'a, F, S>(
+    backends: &'a [Box<dyn Backend>],
+    op: F,
+    mut sink: S,
+) -> Result<(), FiError>
+where
+    F: Fn(&'a dyn Backend) -> BackendFuture<'a>,
+    S: FnMut(Vec<SearchResult>) -> Result<(), FiError>,
+{
+    let mut pending: FuturesUnordered<_> = backends
+        .iter()
+        .map(|b| op(b.as_ref()).map(move |result| (b.name(), result)))
+        .collect();
+
+    while let Some((name, result)) = pending.next().await {
+        match result {
+            Ok(results) => sink(results)?,
+            Err(e) => eprintln!("warning: {}: {}", name, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs `op` on every backend and prints the results in `format`.
+///
+/// With [`OutputFormat::Ndjson`] each backend's results are printed as soon
+/// as it finishes, so fast backends don't wait for slow ones.
+pub async fn print_results<'a, F>(
+    backends: &'a [Box<dyn Backend>],
+    format: OutputFormat,
+    op: F,
+) -> Result<(), FiError>
+where
+    F: Fn(&'a dyn Backend) -> BackendFuture<'a>,
+{
+    let mut collected = Vec::new();
+
+    for_each_backend(backends, op, |results| match format {
+        OutputFormat::Json => {
+            collected.extend(results);
+            Ok(())
+        }
+        OutputFormat::Ndjson => print_items(&results, format),
+    })
+    .await?;
+
+    if format == OutputFormat::Json {
+        print_items(&collected, format)?;
+    }
+
+    Ok(())
+}
+
+/// Prints `items` in `format`: a pretty-printed array for JSON, or one
+/// compact object per line for NDJSON.
+pub fn print_items<T: Serialize>(items: &[T], format: OutputFormat) -> Result<(), FiError> {
+    let mut stdout = io::stdout().lock();
+
+    let written = match format {
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut stdout, items)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(stdout)),
+        OutputFormat::Ndjson => items.iter().try_for_each(|item| {
+            serde_json::to_writer(&mut stdout, item).map_err(io::Error::from)?;
+            writeln!(stdout)
+        }),
+    };
+
+    written
+        .and_then(|_| stdout.flush())
+        .map_err(|e| FiError::OutputFailed(e.to_string()))
+}
@@ -5,8 +5,13 @@ use std::time::Duration;
 use futures::future::join_all;
 
 use crate::backends::Backend;
+use crate::error::BackendError;
 use crate::models::result::SearchResult;
 
+/// The outcome of one operation on every backend, as (backend name, result)
+/// pairs in backend order.
+pub type BackendResults = Vec<(String, Result<Vec<SearchResult>, BackendError>)>;
+
 /// Runs searches across all backends concurrently with progress indicators.
 ///
 /// # Arguments
@@ -16,11 +21,11 @@ use crate::models::result::SearchResult;
 ///
 /// # Returns
 ///
-/// A combined vector of all search results from all backends.
+/// The search results of each backend, or the error it failed with.
 pub async fn run_search_with_progress(
     query: &str,
     backends: &Vec<Box<dyn Backend>>
-) -> Vec<SearchResult> {
+) -> BackendResults {
 
     let mp = MultiProgress::new();
 
@@ -34,21 +39,86 @@ pub async fn run_search_with_progress(
 
         async move {
             let results = backend.search(&q, pb.clone()).await;
-            // Note: progress bar is already finished by the backend's search method
+            // Note: on success the progress bar is already finished by the
+            // backend's search method
+            abandon_on_error(&pb, &name, &results);
             (name, results)
         }
     });
 
     // Run all futures concurrently
-    let results = join_all(futures).await;
+    join_all(futures).await
+}
+
+/// Lists installed packages across all backends concurrently with progress
+/// indicators.
+///
+/// # Arguments
+///
+/// * `backends` - List of backends to query
+///
+/// # Returns
+///
+/// The installed packages reported by each backend, or the error it failed
+/// with.
+pub async fn run_list_installed_with_progress(
+    backends: &Vec<Box<dyn Backend>>
+) -> BackendResults {
 
-    // Combine all results
-    let mut combined = vec![];
+    let mp = MultiProgress::new();
 
-    for (_, list) in results {
-        combined.extend(list);
-    }
+    let futures = backends.iter().map(|backend| {
+        let pb = mp.add(ProgressBar::new_spinner());
+        pb.enable_steady_tick(Duration::from_millis(80));
+
+        let name = backend.name().to_string();
+
+        async move {
+            let results = backend.list_installed(pb.clone()).await;
+            abandon_on_error(&pb, &name, &results);
+            (name, results)
+        }
+    });
 
-    combined
+    join_all(futures).await
 }
 
+/// Checks every backend for pending upgrades concurrently with progress
+/// indicators.
+///
+/// # Arguments
+///
+/// * `backends` - List of backends to query
+///
+/// # Returns
+///
+/// The upgradable packages reported by each backend, or the error it failed
+/// with.
+pub async fn run_list_upgrades_with_progress(
+    backends: &Vec<Box<dyn Backend>>
+) -> BackendResults {
+
+    let mp = MultiProgress::new();
+
+    let futures = backends.iter().map(|backend| {
+        let pb = mp.add(ProgressBar::new_spinner());
+        pb.enable_steady_tick(Duration::from_millis(80));
+
+        let name = backend.name().to_string();
+
+        async move {
+            let results = backend.list_upgrades(pb.clone()).await;
+            abandon_on_error(&pb, &name, &results);
+            (name, results)
+        }
+    });
+
+    join_all(futures).await
+}
+
+/// Stops the spinner of a failed backend, leaving the error on screen.
+fn abandon_on_error<T>(pb: &ProgressBar, name: &str, result: &Result<T, BackendError>) {
+    if let Err(e) = result {
+        pb.abandon_with_message(format!("{} failed: {}", name, e));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::{BTreeSet, HashMap};
 use std::io;
 use std::time::Duration;
 
@@ -8,23 +9,67 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Line},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Terminal,
 };
 
+use crate::backends::Backend;
+use crate::config::UiConfig;
+use crate::error::BackendError;
+use crate::models::info::PackageInfo;
 use crate::models::result::SearchResult;
 
-const MAX_SECTION_HEIGHT: u16 = 12;
+/// What to do with the package the user selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Install the selected packages.
+    Install,
+    /// Remove the selected packages.
+    Remove,
+    /// Upgrade the selected packages.
+    Upgrade,
+}
+
+/// One backend's packages, shown as a bordered list in the TUI.
+pub struct Section {
+    /// Name of the backend, used as the section title.
+    pub backend: String,
+    /// The packages the backend returned.
+    pub items: Vec<SearchResult>,
+    /// The error the backend failed with, shown in place of its packages.
+    pub error: Option<BackendError>,
+}
 
 /// Application state for the TUI interface.
 pub struct AppState {
-    pub sections: Vec<(String, Vec<SearchResult>)>,
+    pub sections: Vec<Section>,
     pub section_states: Vec<ListState>,
     pub active_section: usize,
-    pub selected_result: Option<SearchResult>,
+    /// The packages the user confirmed, empty if the user quit.
+    pub selected_results: Vec<SearchResult>,
+    /// The action applied to `selected_results`. Enter confirms this action,
+    /// `d` switches it to [`Action::Remove`].
+    pub action: Action,
+    /// Packages marked with Space, as (section, index) pairs. When anything
+    /// is marked, Enter and `d` act on the marked set instead of the
+    /// highlighted package.
+    pub marked: BTreeSet<(usize, usize)>,
+    /// Message shown in the status line, replacing the key hints.
+    status: Option<String>,
+    /// The action being reviewed in the confirmation popup, if open.
+    review: Option<Action>,
+    /// Whether the details panel is visible, toggled with `i`.
+    show_details: bool,
+    /// Package details fetched so far, keyed by (section, index).
+    details: HashMap<(usize, usize), Result<PackageInfo, BackendError>>,
+    /// Builds of a package for other architectures than the one shown in its
+    /// row, keyed by (section, index) and cycled through with `a`.
+    arch_variants: HashMap<(usize, usize), Vec<SearchResult>>,
+    /// Section height limit and colors from the configuration.
+    ui: UiConfig,
 }
 
 impl AppState {
@@ -32,13 +77,14 @@ impl AppState {
     ///
     /// # Arguments
     ///
-    /// * `sections` - A vector of (backend_name, results) tuples
-    pub fn new(sections: Vec<(String, Vec<SearchResult>)>) -> Self {
+    /// * `sections` - One section per backend
+    /// * `ui` - Section height limit and colors from the configuration
+    pub fn new(sections: Vec<Section>, ui: UiConfig) -> Self {
         let states = sections
             .iter()
-            .map(|(_, items)| {
+            .map(|section| {
                 let mut state = ListState::default();
-                if !items.is_empty() {
+                if !section.items.is_empty() {
                     state.select(Some(0));
                 }
                 state
@@ -49,9 +95,65 @@ impl AppState {
             sections,
             section_states: states,
             active_section: 0,
-            selected_result: None,
+            selected_results: Vec::new(),
+            action: Action::Install,
+            marked: BTreeSet::new(),
+            status: None,
+            review: None,
+            show_details: false,
+            details: HashMap::new(),
+            arch_variants: HashMap::new(),
+            ui,
+        }
+    }
+
+    /// Collapses the builds of a package for different architectures into
+    /// one row, showing the installed build, or else the one for this
+    /// machine. The others can be switched to with `a`.
+    pub fn group_arches(&mut self) {
+        for (i, section) in self.sections.iter_mut().enumerate() {
+            let mut rows: Vec<Vec<SearchResult>> = Vec::new();
+            for item in section.items.drain(..) {
+                let row = rows.iter_mut().find(|row| {
+                    item.arch.is_some() && row[0].arch.is_some() && row[0].same_package(&item)
+                });
+                match row {
+                    Some(row) => row.push(item),
+                    None => rows.push(vec![item]),
+                }
+            }
+
+            for (j, mut builds) in rows.into_iter().enumerate() {
+                let shown = builds
+                    .iter()
+                    .position(|b| b.installed)
+                    .or_else(|| builds.iter().position(SearchResult::is_native_arch))
+                    .unwrap_or(0);
+                section.items.push(builds.remove(shown));
+                if !builds.is_empty() {
+                    self.arch_variants.insert((i, j), builds);
+                }
+            }
         }
     }
+
+    /// Marks every package in every section.
+    pub fn mark_all(&mut self) {
+        self.marked = self
+            .sections
+            .iter()
+            .enumerate()
+            .flat_map(|(i, section)| (0..section.items.len()).map(move |j| (i, j)))
+            .collect();
+    }
+
+    /// Returns the marked packages in section order.
+    pub fn marked_results(&self) -> Vec<SearchResult> {
+        self.marked
+            .iter()
+            .filter_map(|&(i, j)| self.sections.get(i)?.items.get(j).cloned())
+            .collect()
+    }
 }
 
 /// Runs the TUI interface, allowing the user to browse and select packages.
@@ -59,12 +161,17 @@ impl AppState {
 /// # Arguments
 ///
 /// * `app` - The application state containing search results
+/// * `backends` - Backends used to fetch package details on demand
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` when the user exits or selects a package, or an error
-/// if something goes wrong with the terminal interface.
-pub fn run_tui(app: &mut AppState) -> io::Result<()> {
+/// Returns `Ok(())` when the user exits or confirms a selection, or an error
+/// if something goes wrong with the terminal interface. The chosen packages
+/// and action are left in `app.selected_results` and `app.action`.
+///
+/// Package details are fetched by blocking on the current Tokio runtime, so
+/// this must be called from within a multi-threaded runtime.
+pub fn run_tui(app: &mut AppState, backends: &[Box<dyn Backend>]) -> io::Result<()> {
     // Enter alternate screen mode and enable raw mode
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -77,32 +184,77 @@ pub fn run_tui(app: &mut AppState) -> io::Result<()> {
     let result = loop {
         terminal.draw(|f| draw_ui(f, app))?;
 
-        if event::poll(Duration::from_millis(200))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => {
-                        break Ok(());
-                    }
-
-                    KeyCode::Char('j') | KeyCode::Down => move_down(app),
-                    KeyCode::Char('k') | KeyCode::Up => move_up(app),
-                    KeyCode::Char('g') => jump_top(app),
-                    KeyCode::Char('G') => jump_bottom(app),
+        // Fetch details after drawing so the panel shows a loading message
+        if app.show_details && load_details(app, backends) {
+            continue;
+        }
 
-                    KeyCode::Char('h') | KeyCode::Left => prev_section(app),
-                    KeyCode::Char('l') | KeyCode::Right => next_section(app),
-                    KeyCode::Tab => next_section(app),
-                    KeyCode::BackTab => prev_section(app),
+        if event::poll(Duration::from_millis(200))?
+            && let Event::Key(key) = event::read()?
+        {
+            app.status = None;
 
+            // The review popup captures all keys until it is closed
+            if let Some(action) = app.review {
+                match key.code {
                     KeyCode::Enter => {
-                        if let Some(item) = get_selected_item(app) {
-                            app.selected_result = Some(item);
+                        let (applicable, _) = review_items(app, action);
+                        if applicable.is_empty() {
+                            app.review = None;
+                            app.status = Some("Nothing to do".to_string());
+                        } else {
+                            app.selected_results = applicable;
+                            app.action = action;
                             break Ok(());
                         }
                     }
-
+                    KeyCode::Esc | KeyCode::Char('q') => app.review = None,
                     _ => {}
                 }
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') => {
+                    break Ok(());
+                }
+
+                KeyCode::Char('j') | KeyCode::Down => move_down(app),
+                KeyCode::Char('k') | KeyCode::Up => move_up(app),
+                KeyCode::Char('g') => jump_top(app),
+                KeyCode::Char('G') => jump_bottom(app),
+
+                KeyCode::Char('h') | KeyCode::Left => prev_section(app),
+                KeyCode::Char('l') | KeyCode::Right => next_section(app),
+                KeyCode::Tab => next_section(app),
+                KeyCode::BackTab => prev_section(app),
+
+                KeyCode::Enter if !app.marked.is_empty() => app.review = Some(app.action),
+                KeyCode::Enter => {
+                    if let Some(item) = get_selected_item(app)
+                        && check_action(app, &item, app.action)
+                    {
+                        app.selected_results = vec![item];
+                        break Ok(());
+                    }
+                }
+
+                KeyCode::Char(' ') => toggle_mark(app),
+                KeyCode::Char('i') => app.show_details = !app.show_details,
+                KeyCode::Char('a') => next_arch(app),
+
+                KeyCode::Char('d') if !app.marked.is_empty() => app.review = Some(Action::Remove),
+                KeyCode::Char('d') => {
+                    if let Some(item) = get_selected_item(app)
+                        && check_action(app, &item, Action::Remove)
+                    {
+                        app.selected_results = vec![item];
+                        app.action = Action::Remove;
+                        break Ok(());
+                    }
+                }
+
+                _ => {}
             }
         }
     };
@@ -134,7 +286,7 @@ fn prev_section(app: &mut AppState) {
 }
 
 fn move_down(app: &mut AppState) {
-    let (_, items) = &app.sections[app.active_section];
+    let items = &app.sections[app.active_section].items;
     if items.is_empty() { return; }
 
     let state = &mut app.section_states[app.active_section];
@@ -146,7 +298,7 @@ fn move_down(app: &mut AppState) {
 }
 
 fn move_up(app: &mut AppState) {
-    let (_, items) = &app.sections[app.active_section];
+    let items = &app.sections[app.active_section].items;
     if items.is_empty() { return; }
 
     let state = &mut app.section_states[app.active_section];
@@ -158,76 +310,211 @@ fn move_up(app: &mut AppState) {
 }
 
 fn jump_top(app: &mut AppState) {
-    let (_, items) = &app.sections[app.active_section];
+    let items = &app.sections[app.active_section].items;
     if items.is_empty() { return; }
     app.section_states[app.active_section].select(Some(0));
 }
 
 fn jump_bottom(app: &mut AppState) {
-    let (_, items) = &app.sections[app.active_section];
+    let items = &app.sections[app.active_section].items;
     if items.is_empty() { return; }
     app.section_states[app.active_section].select(Some(items.len() - 1));
 }
 
+fn toggle_mark(app: &mut AppState) {
+    let Some(index) = app.section_states[app.active_section].selected() else {
+        return;
+    };
+
+    let key = (app.active_section, index);
+    if !app.marked.remove(&key) {
+        app.marked.insert(key);
+    }
+}
+
+/// Switches the highlighted package to its build for the next architecture.
+fn next_arch(app: &mut AppState) {
+    let Some(key) = selected_key(app) else {
+        return;
+    };
+    let Some(builds) = app.arch_variants.get_mut(&key) else {
+        app.status = Some("No other architectures for this package".to_string());
+        return;
+    };
+
+    let next = builds.remove(0);
+    let shown = std::mem::replace(&mut app.sections[key.0].items[key.1], next);
+    builds.push(shown);
+
+    // The details were fetched for the previous build
+    app.details.remove(&key);
+}
+
 fn get_selected_item(app: &mut AppState) -> Option<SearchResult> {
-    let (_, items) = &app.sections[app.active_section];
+    let items = &app.sections[app.active_section].items;
     let state = &app.section_states[app.active_section];
     items.get(state.selected()?).cloned()
 }
 
+fn selected_key(app: &AppState) -> Option<(usize, usize)> {
+    let index = app.section_states[app.active_section].selected()?;
+    Some((app.active_section, index))
+}
+
+// ---------------------------------------------------------------------
+// Details
+// ---------------------------------------------------------------------
+
+/// Fetches details for the highlighted package if they aren't cached yet.
+///
+/// Returns `true` if a fetch happened and the UI should be redrawn.
+fn load_details(app: &mut AppState, backends: &[Box<dyn Backend>]) -> bool {
+    let Some(key) = selected_key(app) else {
+        return false;
+    };
+    if app.details.contains_key(&key) {
+        return false;
+    }
+    let Some(item) = get_selected_item(app) else {
+        return false;
+    };
+
+    let details = match backends.iter().find(|b| b.name() == item.backend) {
+        Some(backend) => tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(backend.info(&item))
+        }),
+        None => Err(BackendError::CommandNotFound(item.backend.clone())),
+    };
+
+    app.details.insert(key, details);
+    true
+}
+
+/// Checks that `action` makes sense for `item` given its install state,
+/// setting a status message if it doesn't.
+fn check_action(app: &mut AppState, item: &SearchResult, action: Action) -> bool {
+    if is_applicable(item, action) {
+        return true;
+    }
+
+    app.status = Some(match action {
+        Action::Install => format!("{} is already installed", item.name),
+        Action::Remove | Action::Upgrade => format!("{} is not installed", item.name),
+    });
+    false
+}
+
+/// Returns `true` if `action` can be applied to `item` given its install
+/// state.
+fn is_applicable(item: &SearchResult, action: Action) -> bool {
+    match action {
+        Action::Install => !item.installed,
+        Action::Remove | Action::Upgrade => item.installed,
+    }
+}
+
+/// Splits the marked packages into those `action` applies to and those it
+/// would skip.
+fn review_items(app: &AppState, action: Action) -> (Vec<SearchResult>, Vec<SearchResult>) {
+    app.marked_results()
+        .into_iter()
+        .partition(|item| is_applicable(item, action))
+}
+
 // ---------------------------------------------------------------------
 // UI Rendering
 // ---------------------------------------------------------------------
 
 fn draw_ui(f: &mut ratatui::Frame, app: &mut AppState) {
+    let colors = app.ui.colors;
+    let outer = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(f.size());
+
+    // The details panel takes the right side of the screen when visible
+    let (list_area, details_area) = if app.show_details {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(outer[0]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (outer[0], None)
+    };
+
+    let max_height = app.ui.max_section_height;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             app.sections
                 .iter()
-                .map(|(_, items)| Constraint::Length(section_height(items)))
+                .map(|section| Constraint::Length(section_height(section, max_height)))
+                .chain([Constraint::Min(0)])
                 .collect::<Vec<_>>(),
         )
-        .split(f.size());
+        .split(list_area);
 
-    for (i, (title, items)) in app.sections.iter().enumerate() {
+    for (i, section) in app.sections.iter().enumerate() {
         let area = chunks[i];
+        let items = &section.items;
 
         let is_active = i == app.active_section;
 
+        // Failed backends are drawn in red with the failure in the title
+        let (color, title) = match &section.error {
+            Some(e) => (colors.error, format!("{} — {}", section.backend, e.summary())),
+            None => (colors.accent, section.backend.clone()),
+        };
+
         let border_style = Style::default()
-            .fg(Color::Indexed(2))
+            .fg(color)
             .add_modifier(if is_active { Modifier::BOLD } else { Modifier::DIM });
 
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(border_style)
-            .title(Span::styled(title.clone(), border_style));
+            .title(Span::styled(title, border_style));
 
         if items.is_empty() {
-            let text = Paragraph::new(" No packages matched ")
-                .block(block);
-            f.render_widget(text, area);
+            let text = match &section.error {
+                Some(e) => Paragraph::new(format!(" {}", e))
+                    .style(Style::default().fg(colors.error))
+                    .wrap(Wrap { trim: false }),
+                None => Paragraph::new(" No packages matched "),
+            };
+            f.render_widget(text.block(block), area);
             continue;
         }
 
         let list_items: Vec<ListItem> = items
             .iter()
-            .map(|pkg| {
-                // Format: name [version] — description
-                let version_info = pkg
-                    .version
-                    .as_ref()
-                    .map(|v| format!(" [{}]", v))
-                    .unwrap_or_default();
-                
+            .enumerate()
+            .map(|(j, pkg)| {
+                // Format: name [version] (origin) — description
+                let version_info =
+                    version_info(pkg) + &origin_info(pkg) + &other_arches(app, (i, j));
+
+                // Installed packages get a check mark and their own color
+                let (marker, style) = if pkg.installed {
+                    ("✓ ", Style::default().fg(colors.installed))
+                } else {
+                    ("  ", Style::default())
+                };
+
+                // Multi-select checkbox
+                let checkbox = if app.marked.contains(&(i, j)) { "[x] " } else { "[ ] " };
+
                 let display_text = if pkg.description.is_empty() {
-                    format!("{}{}", pkg.name, version_info)
+                    format!("{}{}{}{}", checkbox, marker, pkg.name, version_info)
                 } else {
-                    format!("{}{} — {}", pkg.name, version_info, pkg.description)
+                    format!(
+                        "{}{}{}{} — {}",
+                        checkbox, marker, pkg.name, version_info, pkg.description
+                    )
                 };
-                
-                ListItem::new(Line::from(Span::raw(display_text)))
+
+                ListItem::new(Line::from(Span::styled(display_text, style)))
             })
             .collect();
 
@@ -236,20 +523,234 @@ fn draw_ui(f: &mut ratatui::Frame, app: &mut AppState) {
             .highlight_symbol(" ")
             .highlight_style(
                 Style::default()
-                    .fg(Color::Indexed(2))
+                    .fg(colors.accent)
                     .add_modifier(Modifier::BOLD),
             );
 
         f.render_stateful_widget(list, area, &mut app.section_states[i]);
     }
+
+    let status = match &app.status {
+        _ if app.review.is_some() => Span::styled(
+            " enter: confirm · esc: back",
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+        Some(message) => {
+            Span::styled(format!(" {}", message), Style::default().fg(colors.status))
+        }
+        None => {
+            Span::styled(key_hints(app.action), Style::default().add_modifier(Modifier::DIM))
+        }
+    };
+    f.render_widget(Paragraph::new(Line::from(status)), outer[1]);
+
+    if let Some(area) = details_area {
+        draw_details(f, app, area);
+    }
+
+    if let Some(action) = app.review {
+        draw_review(f, app, action, outer[0]);
+    }
+}
+
+/// Draws the confirmation popup listing the marked packages per backend.
+fn draw_review(f: &mut ratatui::Frame, app: &AppState, action: Action, area: Rect) {
+    let (applicable, skipped) = review_items(app, action);
+    let verb = match action {
+        Action::Install => "Install",
+        Action::Remove => "Remove",
+        Action::Upgrade => "Upgrade",
+    };
+
+    let colors = &app.ui.colors;
+    let heading = Style::default().fg(colors.accent).add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(format!(" {} {} package(s):", verb, applicable.len())),
+        Line::from(""),
+    ];
+
+    for section in &app.sections {
+        let backend = &section.backend;
+        let items: Vec<&SearchResult> =
+            applicable.iter().filter(|p| &p.backend == backend).collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        lines.push(Line::from(Span::styled(format!(" {}", backend), heading)));
+        lines.extend(items.iter().map(|p| Line::from(format!("   {}", p.name))));
+    }
+
+    if !skipped.is_empty() {
+        let reason = match action {
+            Action::Install => "already installed",
+            Action::Remove | Action::Upgrade => "not installed",
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(" Skipping {} package(s) that are {}", skipped.len(), reason),
+            Style::default().fg(colors.status),
+        )));
+    }
+
+    let border_style = Style::default().fg(colors.accent);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(Span::styled("review", border_style));
+
+    let popup = centered_rect(area, 60, 60);
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), popup);
+}
+
+/// Returns a rectangle of the given percentage size centered in `area`.
+fn centered_rect(area: Rect, width_percent: u16, height_percent: u16) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - height_percent) / 2),
+            Constraint::Percentage(height_percent),
+            Constraint::Percentage((100 - height_percent) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - width_percent) / 2),
+            Constraint::Percentage(width_percent),
+            Constraint::Percentage((100 - width_percent) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+fn draw_details(f: &mut ratatui::Frame, app: &AppState, area: Rect) {
+    let colors = &app.ui.colors;
+    let border_style = Style::default().fg(colors.accent);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(Span::styled("details", border_style));
+
+    let selected = selected_key(app).map(|(i, j)| &app.sections[i].items[j]);
+    let details = selected_key(app).and_then(|key| app.details.get(&key));
+
+    let lines = match details {
+        None if selected_key(app).is_none() => vec![Line::from(" No package selected")],
+        None => vec![Line::from(" Loading details...")],
+        Some(Err(e)) => vec![Line::from(Span::styled(
+            format!(" {}", e),
+            Style::default().fg(colors.error),
+        ))],
+        Some(Ok(info)) => details_lines(info, selected, colors.accent),
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+/// Formats package details, followed by the backend-specific metadata of the
+/// search result they were fetched for.
+fn details_lines<'a>(
+    info: &'a PackageInfo,
+    pkg: Option<&'a SearchResult>,
+    accent: Color,
+) -> Vec<Line<'a>> {
+    let label_style = Style::default().add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![
+        Line::from(Span::styled(info.name.as_str(), label_style.fg(accent))),
+        Line::from(""),
+    ];
+
+    for (label, value) in info.fields() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<12}", label), label_style),
+            Span::raw(value),
+        ]));
+    }
+
+    for (key, value) in pkg.iter().flat_map(|pkg| &pkg.metadata) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<12}", key), label_style),
+            Span::raw(value.as_str()),
+        ]));
+    }
+
+    if !info.description.is_empty() {
+        lines.push(Line::from(""));
+        lines.extend(info.description.lines().map(Line::from));
+    }
+
+    if !info.dependencies.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Dependencies", label_style)));
+        lines.extend(info.dependencies.iter().map(|d| Line::from(format!("  {}", d))));
+    }
+
+    lines
+}
+
+/// Formats the version column, showing the installed version when it
+/// differs from the one the backend offers.
+fn version_info(pkg: &SearchResult) -> String {
+    match (&pkg.installed_version, &pkg.version) {
+        (Some(installed), Some(available)) if pkg.installed && installed != available => {
+            format!(" [{} installed, {} available]", installed, available)
+        }
+        (Some(installed), _) if pkg.installed => format!(" [{} installed]", installed),
+        (_, Some(version)) => format!(" [{}]", version),
+        (None, None) if pkg.installed => " [installed]".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Formats where a package comes from and what it is built for, for
+/// backends that offer the same package from several remotes, branches or
+/// architectures.
+fn origin_info(pkg: &SearchResult) -> String {
+    let parts: Vec<&str> = [&pkg.origin, &pkg.branch, &pkg.arch]
+        .into_iter()
+        .filter_map(|part| part.as_deref())
+        .collect();
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", parts.join(", "))
+    }
+}
+
+/// Lists the architectures a package can be switched to with `a`.
+fn other_arches(app: &AppState, key: (usize, usize)) -> String {
+    let Some(builds) = app.arch_variants.get(&key) else {
+        return String::new();
+    };
+
+    let arches: Vec<&str> = builds.iter().filter_map(|b| b.arch.as_deref()).collect();
+    format!(" (also {})", arches.join(", "))
+}
+
+/// Returns the key hints shown in the status line.
+fn key_hints(action: Action) -> String {
+    match action {
+        Action::Install => {
+            " enter: install · space: mark · a: arch · d: remove · i: details · q: quit".into()
+        }
+        Action::Remove => " enter: remove · space: mark · i: details · q: quit".into(),
+        Action::Upgrade => " enter: upgrade · space: mark · i: details · q: quit".into(),
+    }
 }
 
-fn section_height(items: &Vec<SearchResult>) -> u16 {
-    if items.is_empty() {
+fn section_height(section: &Section, max_height: u16) -> u16 {
+    if section.items.is_empty() {
         return 3;
     }
 
-    let size = items.len() as u16 + 2;
-    size.min(MAX_SECTION_HEIGHT)
+    let size = section.items.len() as u16 + 2;
+    size.min(max_height)
 }
 
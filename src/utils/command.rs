@@ -0,0 +1,195 @@
+//! Helpers for running package manager commands and classifying failures.
+
+use std::io;
+use std::process::{Output, Stdio};
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::BackendError;
+
+/// How long a command whose output is captured may run before it is killed.
+///
+/// Generous because the first search of the day may refresh repository
+/// metadata.
+const OUTPUT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Runs a command, capturing its output, and returns stdout on success.
+///
+/// # Arguments
+///
+/// * `cmd` - The command to run
+///
+/// # Returns
+///
+/// The command's standard output, or a [`BackendError`] classifying why it
+/// failed.
+pub async fn capture(cmd: &mut Command) -> Result<String, BackendError> {
+    capture_allowing(cmd, &[]).await
+}
+
+/// Like [`capture`], but also treats the exit codes in `ok_codes` as success.
+///
+/// Some commands use non-zero exit codes to report results, e.g.
+/// `dnf check-upgrade` exits with 100 when upgrades are available.
+pub async fn capture_allowing(cmd: &mut Command, ok_codes: &[i32]) -> Result<String, BackendError> {
+    let command = describe(cmd);
+    cmd.kill_on_drop(true);
+
+    let output: Output = tokio::time::timeout(OUTPUT_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| BackendError::Timeout(command.clone()))?
+        .map_err(|e| spawn_error(&command, e))?;
+
+    let code = output.status.code();
+    if output.status.success() || code.is_some_and(|c| ok_codes.contains(&c)) {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    Err(classify_failure(command, code, &String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Runs a command with the terminal attached, for operations that show
+/// progress or prompt the user (installs, removals, upgrades).
+///
+/// Standard error goes to the terminal, so failures carry no captured output.
+pub async fn run(cmd: &mut Command) -> Result<(), BackendError> {
+    let command = describe(cmd);
+
+    let status = cmd.status().await.map_err(|e| spawn_error(&command, e))?;
+    if status.success() {
+        return Ok(());
+    }
+
+    Err(BackendError::CommandFailed {
+        command,
+        code: status.code(),
+        stderr: String::new(),
+    })
+}
+
+/// Like [`capture`], but writes `input` to the command's standard input.
+pub async fn capture_with_input(cmd: &mut Command, input: &str) -> Result<String, BackendError> {
+    let command = describe(cmd);
+    cmd.stderr(Stdio::piped());
+
+    let output = tokio::time::timeout(OUTPUT_TIMEOUT, communicate(cmd, &command, input))
+        .await
+        .map_err(|_| BackendError::Timeout(command.clone()))??;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(classify_failure(command, output.status.code(), &stderr))
+}
+
+/// Like [`run`], but writes `input` to the command's standard input and
+/// returns its standard output, for commands that show progress on the
+/// terminal while reporting their result on stdout.
+pub async fn run_with_input(cmd: &mut Command, input: &str) -> Result<String, BackendError> {
+    let command = describe(cmd);
+    cmd.stderr(Stdio::inherit());
+
+    let output = communicate(cmd, &command, input).await?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    Err(BackendError::CommandFailed {
+        command,
+        code: output.status.code(),
+        stderr: String::new(),
+    })
+}
+
+/// Spawns a command, writes `input` to its standard input and waits for it
+/// to exit, collecting standard output.
+async fn communicate(
+    cmd: &mut Command,
+    command: &str,
+    input: &str,
+) -> Result<Output, BackendError> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| spawn_error(command, e))?;
+
+    // A command that exits without reading its input is left to report
+    // through its exit status
+    if let Some(mut stdin) = child.stdin.take() {
+        match stdin.write_all(input.as_bytes()).await {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(spawn_error(command, e)),
+            _ => {}
+        }
+    }
+
+    child.wait_with_output().await.map_err(|e| spawn_error(command, e))
+}
+
+/// Formats a command line for error messages.
+fn describe(cmd: &Command) -> String {
+    let cmd = cmd.as_std();
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Maps a failure to start a command.
+fn spawn_error(command: &str, err: io::Error) -> BackendError {
+    match err.kind() {
+        io::ErrorKind::NotFound => {
+            let program = command.split(' ').next().unwrap_or(command);
+            BackendError::CommandNotFound(program.to_string())
+        }
+        io::ErrorKind::PermissionDenied => BackendError::PermissionDenied(err.to_string()),
+        _ => BackendError::CommandFailed {
+            command: command.to_string(),
+            code: None,
+            stderr: err.to_string(),
+        },
+    }
+}
+
+/// Classifies a non-zero exit by looking for well-known messages in stderr.
+fn classify_failure(command: String, code: Option<i32>, stderr: &str) -> BackendError {
+    let lower = stderr.to_lowercase();
+    let last_line = || stderr.trim().lines().last().unwrap_or("").to_string();
+
+    const PERMISSION: &[&str] = &[
+        "permission denied",
+        "must be root",
+        "superuser",
+        "a password is required",
+        "not allowed",
+    ];
+    const NETWORK: &[&str] = &[
+        "could not resolve",
+        "couldn't resolve",
+        "temporary failure in name resolution",
+        "failed to download",
+        "cannot download",
+        "network is unreachable",
+        "connection refused",
+        "timed out",
+        "failed to get a 200 ok response",
+    ];
+
+    if PERMISSION.iter().any(|p| lower.contains(p)) {
+        BackendError::PermissionDenied(last_line())
+    } else if NETWORK.iter().any(|p| lower.contains(p)) {
+        BackendError::NetworkError(last_line())
+    } else {
+        BackendError::CommandFailed {
+            command,
+            code,
+            stderr: stderr.to_string(),
+        }
+    }
+}
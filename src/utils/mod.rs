@@ -1 +1,4 @@
-// Utility functions for the fi package manager
+//! Utility functions for the fi package manager.
+
+pub mod command;
+pub mod version;
@@ -0,0 +1,54 @@
+//! Version comparison shared by backends whose tools don't report
+//! pending upgrades.
+
+/// Returns `true` if version `candidate` is newer than `current`.
+///
+/// Compares the numeric `major.minor.patch` components, then treats a release
+/// as newer than a pre-release of the same version. Build metadata is ignored.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parse(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.split('+').next().unwrap_or(version);
+        let (numbers, pre) = match version.split_once('-') {
+            Some((numbers, pre)) => (numbers, Some(pre)),
+            None => (version, None),
+        };
+        let numbers = numbers
+            .split('.')
+            .map(|n| n.parse().unwrap_or(0))
+            .collect();
+        (numbers, pre)
+    }
+
+    let (candidate_numbers, candidate_pre) = parse(candidate);
+    let (current_numbers, current_pre) = parse(current);
+
+    match candidate_numbers.cmp(&current_numbers) {
+        std::cmp::Ordering::Equal => match (candidate_pre, current_pre) {
+            (None, Some(_)) => true,
+            (Some(a), Some(b)) => a > b,
+            _ => false,
+        },
+        ordering => ordering.is_gt(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_numeric_components() {
+        assert!(is_newer("1.10.0", "1.9.3"));
+        assert!(is_newer("2.0", "1.99.99"));
+        assert!(!is_newer("1.2.3", "1.2.3"));
+        assert!(!is_newer("1.2.2", "1.2.3"));
+    }
+
+    #[test]
+    fn orders_pre_releases_before_releases() {
+        assert!(is_newer("1.0.0", "1.0.0-rc.1"));
+        assert!(is_newer("1.0.0-rc.2", "1.0.0-rc.1"));
+        assert!(!is_newer("1.0.0-rc.1", "1.0.0"));
+        assert!(!is_newer("1.0.0+build.2", "1.0.0+build.1"));
+    }
+}